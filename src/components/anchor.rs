@@ -0,0 +1,70 @@
+//! This module is responsible for laying out UI elements relative to a corner, edge or the center of the window, so
+//! they stay in place when the window is resized, and scale for high-DPI displays
+
+use apricot::app::App;
+
+/// The point of the window a UI element is placed relative to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+/// Where a UI element goes on the screen, and how big it is, before scaling
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout {
+    pub anchor: Anchor,
+    /// How far the element is from the anchored edges, in unscaled pixels. Anchored to a center, this moves the
+    /// element right and down instead
+    pub margin: nalgebra_glm::Vec2,
+    /// The size of the element, in unscaled pixels
+    pub size: nalgebra_glm::Vec2,
+}
+
+impl Layout {
+    /// Creates a new layout
+    pub fn new(anchor: Anchor, margin: nalgebra_glm::Vec2, size: nalgebra_glm::Vec2) -> Self {
+        Self {
+            anchor,
+            margin,
+            size,
+        }
+    }
+
+    /// The top-left corner of the element on the screen
+    pub fn position(&self, window_size: nalgebra_glm::Vec2, scale: f32) -> nalgebra_glm::Vec2 {
+        let margin = self.margin * scale;
+        let size = self.size * scale;
+        let x = match self.anchor {
+            Anchor::TopLeft | Anchor::Left | Anchor::BottomLeft => margin.x,
+            Anchor::Top | Anchor::Center | Anchor::Bottom => {
+                (window_size.x - size.x) / 2.0 + margin.x
+            }
+            Anchor::TopRight | Anchor::Right | Anchor::BottomRight => {
+                window_size.x - margin.x - size.x
+            }
+        };
+        let y = match self.anchor {
+            Anchor::TopLeft | Anchor::Top | Anchor::TopRight => margin.y,
+            Anchor::Left | Anchor::Center | Anchor::Right => {
+                (window_size.y - size.y) / 2.0 + margin.y
+            }
+            Anchor::BottomLeft | Anchor::Bottom | Anchor::BottomRight => {
+                window_size.y - margin.y - size.y
+            }
+        };
+        nalgebra_glm::vec2(x, y)
+    }
+}
+
+/// The size of the window, in pixels
+pub fn window_size(app: &App) -> nalgebra_glm::Vec2 {
+    nalgebra_glm::vec2(app.window_size.x as f32, app.window_size.y as f32)
+}
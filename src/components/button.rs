@@ -4,11 +4,29 @@ use std::{
 };
 
 use apricot::{app::App, rectangle::Rectangle, render_core::TextureId};
+use hecs::Entity;
+
+use super::{
+    anchor::Layout,
+    label::set_opacity,
+    tween::{Easing, Tween},
+};
+
+/// How opaque a disabled button is drawn
+const DISABLED_OPACITY: f32 = 0.4;
+/// How much smaller a pressed button is drawn, as a fraction of its size
+const PRESSED_INSET: f32 = 0.04;
+/// How long a button takes to fade to or from its hovered texture, in seconds
+const HOVER_FADE_DURATION: f32 = 0.15;
 
 #[derive(Debug)]
 /// Represents different events that could be generated by GUI components
 pub enum Event {
     ButtonClicked(&'static str),
+    /// A planetary body was clicked on in a panel, and should be selected
+    BodyClicked(Entity),
+    /// A star system was clicked on in the galaxy map, and should be entered
+    SystemClicked(usize),
 }
 
 /// Queue for storing events generated by GUI components
@@ -37,57 +55,173 @@ impl EventQueue {
     }
 }
 
+/// What a button looks like, and whether it can be clicked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonState {
+    /// Clickable, and not under the mouse
+    Enabled,
+    /// Clickable, and under the mouse
+    Hovered,
+    /// Under the mouse while the mouse button is held down
+    Pressed,
+    /// Not clickable. Drawn faded out
+    Disabled,
+}
+
 /// Represents a clickable button
 pub struct Button {
     /// Unique identifier for the button
     id: &'static str,
-    /// The rectangle defining the button's position and size
-    rect: Rectangle,
+    /// Where the button goes on the screen, relative to the window
+    layout: Layout,
+    /// The top-left corner of the button on the screen, recomputed from the layout when the window changes
+    position: nalgebra_glm::Vec2,
+    /// The size of the button on the screen, recomputed from the layout when the window changes
+    size: nalgebra_glm::Vec2,
+    /// Why the button can't be clicked, or None if it can
+    disabled_reason: Option<&'static str>,
+    /// What the button does as rich text markup, shown when it's hovered
+    tooltip: &'static str,
     /// The texture ID for the normal state of the button
     texture_id: TextureId,
     /// The texture ID for the hovered state of the button
     hovered_texture_id: TextureId,
+    /// How opaque the hovered texture is drawn over the normal one, fading in and out as the mouse moves on and off
+    hover_fade: Tween<f32>,
     /// The event queue that stores events triggered by this button
     event_queue: Arc<EventQueue>,
 }
 
 impl Button {
-    /// Creates a new button
+    /// Creates a new button. It has no size until it's laid out with `relayout`
     pub fn new(
         id: &'static str,
-        rect: Rectangle,
+        layout: Layout,
         texture_id: TextureId,
         hovered_texture_id: TextureId,
         event_queue: Arc<EventQueue>,
     ) -> Self {
         Self {
             id,
-            rect,
+            layout,
+            position: nalgebra_glm::vec2(0.0, 0.0),
+            size: nalgebra_glm::vec2(0.0, 0.0),
+            disabled_reason: None,
+            tooltip: "",
             texture_id,
             hovered_texture_id,
+            hover_fade: Tween::fixed(0.0),
             event_queue,
         }
     }
 
-    /// Checks if the button is being hovered and clicked
+    /// Recomputes where the button is on the screen, for a window size and UI scale
+    pub fn relayout(&mut self, window_size: nalgebra_glm::Vec2, scale: f32) {
+        self.position = self.layout.position(window_size, scale);
+        self.size = self.layout.size * scale;
+    }
+
+    /// Sets the rich text markup shown when the button is hovered
+    pub fn with_tooltip(mut self, tooltip: &'static str) -> Self {
+        self.tooltip = tooltip;
+        self
+    }
+
+    /// The rich text markup to show when the button is hovered, including why it's disabled if it is
+    pub fn tooltip(&self) -> String {
+        match self.disabled_reason {
+            Some(reason) => format!(
+                "{}\n[color=orange]unavailable: {}[/color]",
+                self.tooltip, reason
+            ),
+            None => self.tooltip.to_string(),
+        }
+    }
+
+    /// The unique identifier of the button
+    pub fn id(&self) -> &'static str {
+        self.id
+    }
+
+    /// Disables the button, with a reason shown when it's hovered, or enables it again if the reason is None
+    pub fn set_disabled(&mut self, reason: Option<&'static str>) {
+        self.disabled_reason = reason;
+    }
+
+    /// Whether the mouse is over the button
+    pub fn is_hovered(&self, app: &App) -> bool {
+        self.rect().contains_point(&app.mouse_pos)
+    }
+
+    /// What the button looks like this tick
+    pub fn state(&self, app: &App) -> ButtonState {
+        if self.disabled_reason.is_some() {
+            ButtonState::Disabled
+        } else if !self.is_hovered(app) {
+            ButtonState::Enabled
+        } else if app.mouse_left_down {
+            ButtonState::Pressed
+        } else {
+            ButtonState::Hovered
+        }
+    }
+
+    /// Checks if the button is being hovered and clicked. Disabled buttons can't be clicked
     pub fn update(&mut self, app: &App) {
-        let is_hovered = self.rect.contains_point(&app.mouse_pos);
-        if is_hovered && app.mouse_left_clicked {
+        if self.disabled_reason.is_none() && self.is_hovered(app) && app.mouse_left_clicked {
             self.event_queue.push(Event::ButtonClicked(self.id));
         }
     }
 
-    /// Renders a button to the screen
-    pub fn render(&mut self, app: &App) {
-        let is_hovered = self.rect.contains_point(&app.mouse_pos);
+    /// Renders a button to the screen. Hovered buttons fade to their hovered texture, disabled buttons are faded
+    /// out, and pressed buttons are drawn a little smaller
+    pub fn render(&mut self, app: &App, program_2d_id: u32) {
+        let state = self.state(app);
+        let hover = match state {
+            ButtonState::Hovered | ButtonState::Pressed => 1.0,
+            ButtonState::Enabled | ButtonState::Disabled => 0.0,
+        };
+        if self.hover_fade.end_value() != hover {
+            self.hover_fade
+                .retarget(app.seconds, hover, HOVER_FADE_DURATION, Easing::QuadOut);
+        }
+
+        if state == ButtonState::Disabled {
+            set_opacity(program_2d_id, DISABLED_OPACITY);
+        }
         app.renderer.copy_texture(
-            self.rect,
-            if is_hovered {
-                self.hovered_texture_id
-            } else {
-                self.texture_id
-            },
+            self.drawn_rect(state),
+            self.texture_id,
             Rectangle::new(0.0, 0.0, 360.0, 360.0),
         );
+        let hover_opacity = self.hover_fade.value(app.seconds);
+        if hover_opacity > 0.0 {
+            set_opacity(program_2d_id, hover_opacity);
+            app.renderer.copy_texture(
+                self.drawn_rect(state),
+                self.hovered_texture_id,
+                Rectangle::new(0.0, 0.0, 360.0, 360.0),
+            );
+        }
+        set_opacity(program_2d_id, 1.0);
+    }
+
+    /// The rectangle the button covers on the screen
+    fn rect(&self) -> Rectangle {
+        Rectangle::new(self.position.x, self.position.y, self.size.x, self.size.y)
+    }
+
+    /// The rectangle the button is drawn in, a little smaller than it covers while it's pressed
+    fn drawn_rect(&self, state: ButtonState) -> Rectangle {
+        if state != ButtonState::Pressed {
+            return self.rect();
+        }
+        let inset = self.size * PRESSED_INSET;
+        Rectangle::new(
+            self.position.x + inset.x,
+            self.position.y + inset.y,
+            self.size.x - inset.x * 2.0,
+            self.size.y - inset.y * 2.0,
+        )
     }
 }
@@ -0,0 +1,178 @@
+//! This module is responsible for the in-game calendar, which maps turns to dates so players can reason about years and
+//! months, and lets things be scheduled to happen on a date
+
+use chrono::{Duration, NaiveDate};
+
+/// How many turns it takes for earth to go around the sun once, unless the settings change it
+pub const REAL_SECS_PER_GAME_YEAR: f32 = 60.0;
+/// How many days are in a year
+pub const DAYS_PER_YEAR: f32 = 365.25;
+
+/// Maps turns to dates
+pub struct Calendar {
+    /// The date of turn 0
+    start_date: NaiveDate,
+    /// How many days pass each turn
+    days_per_turn: f32,
+}
+
+impl Calendar {
+    /// Creates a new calendar starting at `start_date`, where `days_per_turn` days pass each turn
+    pub fn new(start_date: NaiveDate, days_per_turn: f32) -> Self {
+        Self {
+            start_date,
+            days_per_turn,
+        }
+    }
+
+    /// The date of a turn
+    pub fn date_of_turn(&self, turn: usize) -> NaiveDate {
+        let days = (turn as f32 * self.days_per_turn).floor() as i64;
+        self.start_date + Duration::days(days)
+    }
+
+    /// The first turn on or after a date
+    pub fn turn_of_date(&self, date: NaiveDate) -> usize {
+        let days = (date - self.start_date).num_days().max(0);
+        (days as f32 / self.days_per_turn).ceil() as usize
+    }
+
+    /// The date of a turn, formatted for the HUD
+    pub fn format_turn(&self, turn: usize) -> String {
+        self.date_of_turn(turn).format("%d %b %Y").to_string()
+    }
+
+    /// How many years pass in a number of turns, which may be part way through a turn. Bodies orbit by this, so
+    /// they keep pace with the dates
+    pub fn years(&self, turns: f32) -> f32 {
+        turns * self.days_per_turn / DAYS_PER_YEAR
+    }
+}
+
+/// Things that are scheduled to happen on a date. Items are kept once they're due, so that loading a save from
+/// before they were due makes them due again
+pub struct Schedule<T> {
+    /// The turn each item is due, along with the item
+    items: Vec<(usize, T)>,
+    /// The first turn whose items haven't been taken yet
+    next_turn: usize,
+}
+
+impl<T: Clone> Schedule<T> {
+    /// Creates a new, empty schedule
+    pub fn new() -> Self {
+        Self {
+            items: vec![],
+            next_turn: 0,
+        }
+    }
+
+    /// Schedules an item to be due on the first turn on or after a date
+    pub fn schedule(&mut self, calendar: &Calendar, date: NaiveDate, item: T) {
+        self.items.push((calendar.turn_of_date(date), item));
+    }
+
+    /// Returns every item that came due since the last time this was called, up to and including a turn
+    pub fn take_due(&mut self, turn: usize) -> Vec<T> {
+        let due = self
+            .items
+            .iter()
+            .filter(|(due_turn, _item)| self.next_turn <= *due_turn && *due_turn <= turn)
+            .map(|(_due_turn, item)| item.clone())
+            .collect();
+        self.next_turn = self.next_turn.max(turn + 1);
+        due
+    }
+
+    /// Moves the schedule to a turn, for when a save is loaded. Items due on or before the turn already happened in
+    /// that game, so they're skipped, and items due after it will happen again even if they already did before
+    pub fn rebase(&mut self, turn: usize) {
+        self.next_turn = turn + 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    /// Starts on the first of January, 2200, with a week passing each turn
+    fn weekly() -> Calendar {
+        Calendar::new(date(2200, 1, 1), 7.0)
+    }
+
+    #[test]
+    fn turns_map_to_dates() {
+        let calendar = weekly();
+        assert_eq!(calendar.date_of_turn(0), date(2200, 1, 1));
+        assert_eq!(calendar.date_of_turn(1), date(2200, 1, 8));
+        assert_eq!(calendar.date_of_turn(5), date(2200, 2, 5));
+        assert_eq!(calendar.format_turn(5), "05 Feb 2200");
+    }
+
+    #[test]
+    fn partial_days_are_dropped() {
+        let calendar = Calendar::new(date(2200, 1, 1), 1.5);
+        assert_eq!(calendar.date_of_turn(1), date(2200, 1, 2));
+        assert_eq!(calendar.date_of_turn(2), date(2200, 1, 4));
+    }
+
+    #[test]
+    fn dates_map_to_the_first_turn_on_or_after_them() {
+        let calendar = weekly();
+        assert_eq!(calendar.turn_of_date(date(2200, 1, 1)), 0);
+        assert_eq!(calendar.turn_of_date(date(2200, 1, 8)), 1);
+        assert_eq!(calendar.turn_of_date(date(2200, 1, 9)), 2);
+        assert_eq!(calendar.turn_of_date(date(2199, 6, 1)), 0);
+    }
+
+    #[test]
+    fn a_year_passes_every_year_of_turns() {
+        let calendar = Calendar::new(date(2200, 1, 1), DAYS_PER_YEAR / REAL_SECS_PER_GAME_YEAR);
+        assert!((calendar.years(REAL_SECS_PER_GAME_YEAR) - 1.0).abs() < 1e-5);
+        assert_eq!(
+            calendar.date_of_turn(REAL_SECS_PER_GAME_YEAR as usize),
+            date(2201, 1, 1)
+        );
+    }
+
+    #[test]
+    fn items_are_taken_once_when_due() {
+        let calendar = weekly();
+        let mut schedule = Schedule::new();
+        schedule.schedule(&calendar, date(2200, 1, 15), "milestone");
+        assert!(schedule.take_due(1).is_empty());
+        assert_eq!(schedule.take_due(2), vec!["milestone"]);
+        assert!(schedule.take_due(3).is_empty());
+    }
+
+    #[test]
+    fn items_skipped_over_are_still_taken() {
+        let calendar = weekly();
+        let mut schedule = Schedule::new();
+        schedule.schedule(&calendar, date(2200, 1, 15), "milestone");
+        assert_eq!(schedule.take_due(10), vec!["milestone"]);
+    }
+
+    #[test]
+    fn loading_after_an_item_is_due_skips_it() {
+        let calendar = weekly();
+        let mut schedule = Schedule::new();
+        schedule.schedule(&calendar, date(2200, 1, 15), "milestone");
+        schedule.rebase(4);
+        assert!(schedule.take_due(5).is_empty());
+    }
+
+    #[test]
+    fn loading_before_an_item_is_due_makes_it_due_again() {
+        let calendar = weekly();
+        let mut schedule = Schedule::new();
+        schedule.schedule(&calendar, date(2200, 1, 15), "milestone");
+        assert_eq!(schedule.take_due(3), vec!["milestone"]);
+        schedule.rebase(1);
+        assert_eq!(schedule.take_due(2), vec!["milestone"]);
+    }
+}
@@ -0,0 +1,184 @@
+//! This module is responsible for camera bookmarks, which save where the camera is to a numbered hotkey, and the
+//! focus history, which lets the user go back and forward between bodies they looked at, like in a web browser
+
+use super::input::{Action, InputMap};
+
+/// How many bookmarks there are, one for each bookmark action
+pub const BOOKMARK_COUNT: usize = Action::BOOKMARKS.len();
+/// The oldest focuses are forgotten once the history is this long
+const MAX_HISTORY: usize = 100;
+
+/// A saved camera position
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraBookmark {
+    /// Index of the star system the camera was in
+    pub system: usize,
+    /// Index of the body the camera was looking at, within its system
+    pub selection: usize,
+    /// Up-down view angle
+    pub phi: f32,
+    /// Side-side view angle
+    pub theta: f32,
+    /// How far the camera was from the body
+    pub distance: f32,
+}
+
+/// A body the camera looked at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Focus {
+    /// Index of the star system the body is in
+    pub system: usize,
+    /// Index of the body within its system
+    pub selection: usize,
+}
+
+/// The bodies the camera looked at, in order, and where in that order the camera is now
+pub struct FocusHistory {
+    focuses: Vec<Focus>,
+    /// Index into `focuses` of the current focus
+    current: usize,
+}
+
+impl FocusHistory {
+    /// Creates a new history, starting at a focus
+    pub fn new(focus: Focus) -> Self {
+        Self {
+            focuses: vec![focus],
+            current: 0,
+        }
+    }
+
+    /// Records a new focus. Anything that could be gone forward to is forgotten, like in a web browser
+    pub fn push(&mut self, focus: Focus) {
+        if self.focuses[self.current] == focus {
+            return;
+        }
+        self.focuses.truncate(self.current + 1);
+        self.focuses.push(focus);
+        if self.focuses.len() > MAX_HISTORY {
+            self.focuses.remove(0);
+        }
+        self.current = self.focuses.len() - 1;
+    }
+
+    /// Goes back to the previous focus, if there is one
+    pub fn back(&mut self) -> Option<Focus> {
+        self.current = self.current.checked_sub(1)?;
+        Some(self.focuses[self.current])
+    }
+
+    /// Goes forward to the next focus, if there is one
+    pub fn forward(&mut self) -> Option<Focus> {
+        if self.current + 1 >= self.focuses.len() {
+            return None;
+        }
+        self.current += 1;
+        Some(self.focuses[self.current])
+    }
+}
+
+/// Something the user asked to do with bookmarks or the focus history
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookmarkAction {
+    /// Save the camera to a bookmark
+    Save(usize),
+    /// Jump the camera to a bookmark
+    Recall(usize),
+    /// Go back in the focus history
+    Back,
+    /// Go forward in the focus history
+    Forward,
+}
+
+/// Reads the bookmark and focus history actions, and returns whatever the user asked to do this tick. A bookmark
+/// action saves the bookmark while the save bookmark action is held, and recalls it while the recall bookmark action is
+/// held
+pub fn bookmark_actions(input: &InputMap) -> Vec<BookmarkAction> {
+    let save = input.is_down(Action::SaveBookmark);
+    let recall = input.is_down(Action::RecallBookmark);
+
+    let mut actions = vec![];
+    for (i, action) in Action::BOOKMARKS.iter().enumerate() {
+        if !input.pressed(*action) {
+            continue;
+        }
+        if save {
+            actions.push(BookmarkAction::Save(i));
+        } else if recall {
+            actions.push(BookmarkAction::Recall(i));
+        }
+    }
+    if input.pressed(Action::HistoryBack) {
+        actions.push(BookmarkAction::Back);
+    }
+    if input.pressed(Action::HistoryForward) {
+        actions.push(BookmarkAction::Forward);
+    }
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn focus(selection: usize) -> Focus {
+        Focus {
+            system: 0,
+            selection,
+        }
+    }
+
+    /// A history that looked at bodies 0 to `count - 1`, in order
+    fn history(count: usize) -> FocusHistory {
+        let mut history = FocusHistory::new(focus(0));
+        for selection in 1..count {
+            history.push(focus(selection));
+        }
+        history
+    }
+
+    #[test]
+    fn back_and_forward_walk_the_history() {
+        let mut history = history(3);
+        assert_eq!(history.forward(), None);
+        assert_eq!(history.back(), Some(focus(1)));
+        assert_eq!(history.back(), Some(focus(0)));
+        assert_eq!(history.back(), None);
+        assert_eq!(history.forward(), Some(focus(1)));
+        assert_eq!(history.forward(), Some(focus(2)));
+        assert_eq!(history.forward(), None);
+    }
+
+    #[test]
+    fn pushing_after_going_back_forgets_the_forward_focuses() {
+        let mut history = history(3);
+        history.back();
+        history.back();
+        history.push(focus(5));
+        assert_eq!(history.forward(), None);
+        assert_eq!(history.back(), Some(focus(0)));
+        assert_eq!(history.forward(), Some(focus(5)));
+    }
+
+    #[test]
+    fn pushing_the_current_focus_again_does_nothing() {
+        let mut history = history(2);
+        history.push(focus(1));
+        assert_eq!(history.back(), Some(focus(0)));
+        assert_eq!(history.back(), None);
+    }
+
+    #[test]
+    fn the_oldest_focuses_are_forgotten() {
+        let mut history = history(MAX_HISTORY + 5);
+        let mut oldest = focus(MAX_HISTORY + 4);
+        let mut steps = 0;
+        while let Some(previous) = history.back() {
+            oldest = previous;
+            steps += 1;
+        }
+        assert_eq!(steps, MAX_HISTORY - 1);
+        assert_eq!(oldest, focus(5));
+        assert_eq!(history.forward(), Some(focus(6)));
+    }
+}
@@ -0,0 +1,151 @@
+//! This module is responsible for defining the ways the 3d camera can be controlled, and how each one limits zooming
+
+use super::input::{Action, InputMap};
+
+/// The action for each camera mode
+const MODE_ACTIONS: [(Action, CameraMode); 4] = [
+    (Action::CameraOrbit, CameraMode::Orbit),
+    (Action::CameraFreeFly, CameraMode::FreeFly),
+    (Action::CameraTrack, CameraMode::Track),
+    (Action::CameraTopDown, CameraMode::TopDown),
+];
+
+/// How the 3d camera moves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraMode {
+    /// Swivels around the selected body
+    Orbit,
+    /// Flies freely with WASD, looking around with the mouse
+    FreeFly,
+    /// Swivels around the selected body, turning with it as it goes around the system so it stays the same way up.
+    /// Ships don't have a position in a system yet, so there are no fleets to follow and this tracks bodies instead
+    Track,
+    /// Looks straight down at the orbital plane of the whole system
+    TopDown,
+}
+
+/// How close and how far the camera can be from what it's looking at. Each limit is some number of the selected
+/// body's radii, plus an offset
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZoomLimits {
+    pub min_radii: f32,
+    pub min_offset: f32,
+    pub max_radii: f32,
+    pub max_offset: f32,
+}
+
+impl ZoomLimits {
+    /// The closest the camera can get, for a selected body of some radius
+    pub fn min_distance(&self, body_radius: f32) -> f32 {
+        body_radius * self.min_radii + self.min_offset
+    }
+
+    /// The furthest the camera can get, for a selected body of some radius
+    pub fn max_distance(&self, body_radius: f32) -> f32 {
+        body_radius * self.max_radii + self.max_offset
+    }
+
+    /// Keeps a distance within the limits
+    pub fn clamp(&self, distance: f32, body_radius: f32) -> f32 {
+        distance
+            .max(self.min_distance(body_radius))
+            .min(self.max_distance(body_radius))
+    }
+}
+
+/// Settings for how a camera mode is controlled
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraModeConfig {
+    /// Zoom limits, or None if the mouse wheel doesn't zoom in this mode
+    pub zoom: Option<ZoomLimits>,
+    /// The up-down view angle the camera is locked to, or None if it can be changed with the mouse
+    pub locked_theta: Option<f32>,
+    /// How far the camera moves each tick with the movement keys, in world units
+    pub fly_speed: f32,
+    /// Whether zooming out past the max distance opens the galaxy map
+    pub zoom_out_to_galaxy: bool,
+}
+
+impl CameraMode {
+    /// The settings for this mode
+    pub fn config(&self) -> CameraModeConfig {
+        match self {
+            CameraMode::Orbit => CameraModeConfig {
+                zoom: Some(ZoomLimits {
+                    min_radii: 2.0,
+                    min_offset: 0.0,
+                    max_radii: 40000.0,
+                    max_offset: 234.0,
+                }),
+                locked_theta: None,
+                fly_speed: 0.0,
+                zoom_out_to_galaxy: true,
+            },
+            CameraMode::FreeFly => CameraModeConfig {
+                zoom: None,
+                locked_theta: None,
+                fly_speed: 40.0,
+                zoom_out_to_galaxy: false,
+            },
+            CameraMode::Track => CameraModeConfig {
+                zoom: Some(ZoomLimits {
+                    min_radii: 2.0,
+                    min_offset: 0.0,
+                    max_radii: 200.0,
+                    max_offset: 0.0,
+                }),
+                locked_theta: None,
+                fly_speed: 0.0,
+                zoom_out_to_galaxy: false,
+            },
+            CameraMode::TopDown => CameraModeConfig {
+                zoom: Some(ZoomLimits {
+                    min_radii: 0.0,
+                    min_offset: 2000.0,
+                    max_radii: 0.0,
+                    max_offset: 400000.0,
+                }),
+                // Just shy of straight down, so that the camera's up direction still means something
+                locked_theta: Some(0.005 - std::f32::consts::PI / 2.0),
+                fly_speed: 0.0,
+                zoom_out_to_galaxy: true,
+            },
+        }
+    }
+
+    /// Text to show in the HUD for this mode
+    pub fn label(&self) -> &'static str {
+        match self {
+            CameraMode::Orbit => "orbit",
+            CameraMode::FreeFly => "free-fly",
+            CameraMode::Track => "track",
+            CameraMode::TopDown => "top-down",
+        }
+    }
+}
+
+/// Keeps track of the camera mode, and lets the user change it with hotkeys
+pub struct CameraModeControl {
+    /// The current camera mode
+    pub mode: CameraMode,
+}
+
+impl CameraModeControl {
+    /// Creates a new camera mode control, starting in orbit mode
+    pub fn new() -> Self {
+        Self {
+            mode: CameraMode::Orbit,
+        }
+    }
+
+    /// Changes the camera mode based on the camera mode actions. Returns whether the mode changed
+    pub fn update(&mut self, input: &InputMap) -> bool {
+        let prev_mode = self.mode;
+        for (action, mode) in MODE_ACTIONS {
+            if input.pressed(action) {
+                self.mode = mode;
+            }
+        }
+        self.mode != prev_mode
+    }
+}
@@ -0,0 +1,504 @@
+//! This module is responsible for colonies and their construction queues. Each colony builds one thing at a time from
+//! the front of its queue, paid for up front with credits. Progress is made once a turn, when the turn resolves.
+
+use apricot::{bvh::BVH, render_core::RenderContext};
+use hecs::{Entity, World};
+
+use super::planet::Planet;
+
+/// Credits the empire starts with
+pub const STARTING_CREDITS: u32 = 100;
+/// Credits every colony makes each turn, before its buildings
+const BASE_INCOME: u32 = 10;
+/// Extra credits each mine makes each turn
+const MINE_INCOME: u32 = 5;
+/// Extra credits each million people make each turn
+const POPULATION_INCOME: f32 = 0.2;
+/// How many million people a new colony starts with
+const STARTING_POPULATION: f32 = 10.0;
+/// How many million people a fully habitable body can support. Less habitable bodies support proportionally fewer
+const POPULATION_CAPACITY: f32 = 100.0;
+/// How fast a colony's population grows each turn while it's far below what its body can support
+const GROWTH_RATE: f32 = 0.05;
+/// Radius of an orbital station
+const STATION_RADIUS: f32 = 0.05;
+/// How far out the first station around a body orbits, in body radii. Each station after orbits further out
+const STATION_ORBIT_RADII: f32 = 4.0;
+/// The closest a station orbits its body, so that stations around small bodies still get an orbit line
+const MIN_STATION_ORBIT: f32 = 1.5;
+/// How long a station takes to orbit its body, in years
+const STATION_ORBIT_YEARS: f32 = 0.02;
+/// How many hyperlane jumps away from its system a station's sensors can see
+pub const STATION_SENSOR_RANGE: usize = 2;
+/// How much each station adds to the defense of its system
+pub const STATION_DEFENSE: u32 = 25;
+
+/// What sort of thing a project builds, which decides what happens when it's done
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    /// Stays on the colony's body
+    Building,
+    /// Spawned as a new body orbiting any body in the colony's system
+    Station,
+    /// Spawned as a ship at the colony's system
+    Ship,
+}
+
+/// Something a colony can build
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Project {
+    Mine,
+    Shipyard,
+    Station,
+    Scout,
+    Frigate,
+}
+
+impl Project {
+    /// Every project, in the order they're shown on the construction panel
+    pub const ALL: [Project; 5] = [
+        Project::Mine,
+        Project::Shipyard,
+        Project::Station,
+        Project::Scout,
+        Project::Frigate,
+    ];
+
+    /// The name of the project, as shown to the player
+    pub fn name(&self) -> &'static str {
+        match self {
+            Project::Mine => "mine",
+            Project::Shipyard => "shipyard",
+            Project::Station => "orbital station",
+            Project::Scout => "scout",
+            Project::Frigate => "frigate",
+        }
+    }
+
+    /// The name of the project in the save file
+    pub fn id(&self) -> &'static str {
+        match self {
+            Project::Mine => "mine",
+            Project::Shipyard => "shipyard",
+            Project::Station => "station",
+            Project::Scout => "scout",
+            Project::Frigate => "frigate",
+        }
+    }
+
+    /// Finds the project with a save file name
+    pub fn from_id(id: &str) -> Option<Self> {
+        Project::ALL.into_iter().find(|project| project.id() == id)
+    }
+
+    /// What sort of thing the project builds
+    pub fn category(&self) -> Category {
+        match self {
+            Project::Mine | Project::Shipyard => Category::Building,
+            Project::Station => Category::Station,
+            Project::Scout | Project::Frigate => Category::Ship,
+        }
+    }
+
+    /// How many credits the project costs
+    pub fn cost(&self) -> u32 {
+        match self {
+            Project::Mine => 30,
+            Project::Shipyard => 60,
+            Project::Station => 120,
+            Project::Scout => 20,
+            Project::Frigate => 50,
+        }
+    }
+
+    /// How many turns the project takes to build, once it reaches the front of the queue
+    pub fn build_turns(&self) -> u32 {
+        match self {
+            Project::Mine => 3,
+            Project::Shipyard => 4,
+            Project::Station => 6,
+            Project::Scout => 2,
+            Project::Frigate => 4,
+        }
+    }
+
+    /// Why a colony can't build the project, or None if it can. Ships need a shipyard, which stations have
+    pub fn requirement(&self, colony: &Colony) -> Option<&'static str> {
+        if self.category() == Category::Ship
+            && !colony.buildings.contains(&Project::Shipyard)
+            && colony.stations.is_empty()
+        {
+            Some("needs a shipyard or station")
+        } else {
+            None
+        }
+    }
+}
+
+/// A project waiting in a construction queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueItem {
+    pub project: Project,
+    /// Index of the body the project is built at, in its system. Stations can be built around any body in the
+    /// colony's system, everything else is built at the colony's own body
+    pub target: usize,
+    /// How many more turns of work the project needs
+    pub turns_left: u32,
+}
+
+impl QueueItem {
+    /// How many credits cancelling the project gives back. Everything is refunded if work hasn't started, otherwise
+    /// only the share of the cost for the work not yet done
+    pub fn refund(&self) -> u32 {
+        self.project.cost() * self.turns_left / self.project.build_turns()
+    }
+}
+
+/// Projects a colony is going to build, in order
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ConstructionQueue {
+    items: Vec<QueueItem>,
+}
+
+impl ConstructionQueue {
+    /// The projects in the queue, front first
+    pub fn items(&self) -> &[QueueItem] {
+        &self.items
+    }
+
+    /// Adds a project to the back of the queue, taking its cost out of `credits`
+    pub fn push(
+        &mut self,
+        project: Project,
+        target: usize,
+        credits: &mut u32,
+    ) -> Result<(), String> {
+        if *credits < project.cost() {
+            return Err(format!(
+                "{} costs {} credits, only {} left",
+                project.name(),
+                project.cost(),
+                credits
+            ));
+        }
+        *credits -= project.cost();
+        self.items.push(QueueItem {
+            project,
+            target,
+            turns_left: project.build_turns(),
+        });
+        Ok(())
+    }
+
+    /// Adds a project that's already paid for to the back of the queue, keeping its progress, like when loading a save
+    pub fn restore(&mut self, item: QueueItem) {
+        self.items.push(item);
+    }
+
+    /// Swaps the `i`th project with the one in front of it
+    pub fn move_up(&mut self, i: usize) {
+        if i > 0 && i < self.items.len() {
+            self.items.swap(i - 1, i);
+        }
+    }
+
+    /// Swaps the `i`th project with the one behind it
+    pub fn move_down(&mut self, i: usize) {
+        if i + 1 < self.items.len() {
+            self.items.swap(i, i + 1);
+        }
+    }
+
+    /// Removes the `i`th project from the queue, giving back its refund
+    pub fn cancel(&mut self, i: usize) -> u32 {
+        if i >= self.items.len() {
+            return 0;
+        }
+        self.items.remove(i).refund()
+    }
+
+    /// Removes every project built around a body past `body_count`, without a refund
+    pub fn drop_targets_past(&mut self, body_count: usize) {
+        self.items.retain(|item| item.target < body_count);
+    }
+
+    /// Does a turn of work on the project at the front of the queue, returning it if it's done
+    pub fn advance(&mut self) -> Option<QueueItem> {
+        let front = self.items.first_mut()?;
+        front.turns_left = front.turns_left.saturating_sub(1);
+        if front.turns_left == 0 {
+            Some(self.items.remove(0))
+        } else {
+            None
+        }
+    }
+}
+
+/// A body the empire has settled, which makes credits and builds things
+#[derive(Debug, Clone, PartialEq)]
+pub struct Colony {
+    /// The system the colony is in
+    pub system: usize,
+    /// Index of the colony's body in its system, in the order the system's bodies are spawned
+    pub body: usize,
+    pub name: String,
+    /// The habitability score of the colony's body, from 0 to 1, which decides how big its population can grow
+    pub habitability: f32,
+    /// How many people live in the colony, in millions
+    pub population: f32,
+    pub queue: ConstructionQueue,
+    /// Finished buildings
+    pub buildings: Vec<Project>,
+    /// The body each finished orbital station orbits, as an index like `body`. Stations are spawned around their
+    /// bodies whenever the colony's system is entered
+    pub stations: Vec<usize>,
+}
+
+impl Colony {
+    /// Creates a new colony, with nothing built
+    pub fn new(system: usize, body: usize, name: String, habitability: f32) -> Self {
+        Self {
+            system,
+            body,
+            name,
+            habitability,
+            population: STARTING_POPULATION,
+            queue: ConstructionQueue::default(),
+            buildings: vec![],
+            stations: vec![],
+        }
+    }
+
+    /// Adds a project to the back of the colony's queue, taking its cost out of `credits`. Only stations are built
+    /// at `target`, everything else is built at the colony's own body
+    pub fn enqueue(
+        &mut self,
+        project: Project,
+        target: usize,
+        credits: &mut u32,
+    ) -> Result<(), String> {
+        if let Some(requirement) = project.requirement(self) {
+            return Err(format!("{} {}", project.name(), requirement));
+        }
+        let target = if project.category() == Category::Station {
+            target
+        } else {
+            self.body
+        };
+        self.queue.push(project, target, credits)
+    }
+
+    /// How many of the colony's stations orbit a body
+    pub fn stations_around(&self, body: usize) -> usize {
+        self.stations
+            .iter()
+            .filter(|station| **station == body)
+            .count()
+    }
+
+    /// Drops the stations and queued projects around bodies past `body_count`, the number of natural bodies in the
+    /// colony's system, for saves that are stale or edited by hand. Returns whether the colony's own body is in range
+    pub fn fit_to_bodies(&mut self, body_count: usize) -> bool {
+        self.stations.retain(|body| *body < body_count);
+        self.queue.drop_targets_past(body_count);
+        self.body < body_count
+    }
+
+    /// How many million people the colony's body can support
+    pub fn population_capacity(&self) -> f32 {
+        POPULATION_CAPACITY * self.habitability
+    }
+
+    /// Grows the population for a turn. Growth slows as the population nears what the body can support, and a
+    /// population over that shrinks back down
+    pub fn grow(&mut self) {
+        let capacity = self.population_capacity();
+        let growth =
+            GROWTH_RATE * self.population * (capacity - self.population) / capacity.max(1.0);
+        self.population = (self.population + growth).max(0.0);
+    }
+
+    /// How many credits the colony makes each turn
+    pub fn income(&self) -> u32 {
+        let mines = self
+            .buildings
+            .iter()
+            .filter(|building| **building == Project::Mine)
+            .count() as u32;
+        BASE_INCOME + (self.population * POPULATION_INCOME) as u32 + MINE_INCOME * mines
+    }
+}
+
+/// A ship, built by a colony with a shipyard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ship {
+    pub name: String,
+    /// The system the ship is in
+    pub system: usize,
+}
+
+/// An artificial body, which gives its colony a shipyard, sees nearby systems with its sensors and defends its system
+pub struct Station {
+    /// How many hyperlane jumps away from its system the station can see
+    pub sensor_range: usize,
+    /// How much the station adds to the defense of its system, which makes routes through the system cheaper
+    pub defense: u32,
+}
+
+impl Default for Station {
+    fn default() -> Self {
+        Self {
+            sensor_range: STATION_SENSOR_RANGE,
+            defense: STATION_DEFENSE,
+        }
+    }
+}
+
+/// Spawns the `index`th orbital station around a body, one planet tier below it, and returns the station. Stations
+/// orbit like moons, but are drawn with their own mesh
+pub fn spawn_station(
+    world: &mut World,
+    renderer: &RenderContext,
+    bvh: &mut BVH<Entity>,
+    orbit_segments: usize,
+    parent: Entity,
+    index: usize,
+) -> Entity {
+    let (tier, body_radius, name) = {
+        let planet = world.get::<&Planet>(parent).unwrap();
+        (planet.tier, planet.body_radius, planet.name.clone())
+    };
+    let station = Planet::with_mesh(
+        world,
+        renderer,
+        bvh,
+        orbit_segments,
+        renderer.get_mesh_id_from_name("station").unwrap(),
+        parent,
+        tier + 1,
+        STATION_RADIUS,
+        (body_radius * (STATION_ORBIT_RADII + index as f32)).max(MIN_STATION_ORBIT + index as f32),
+        // Spread stations sharing a body around it, so they don't start on top of each other
+        index as f32 * 2.4,
+        STATION_ORBIT_YEARS,
+        0.0,
+        renderer.get_texture_id_from_name("station").unwrap(),
+        format!("{} Station {}", name, index + 1),
+    );
+    world.insert_one(station, Station::default()).unwrap();
+    station
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A queue with a mine then a station, paid for out of plenty of credits
+    fn queue() -> ConstructionQueue {
+        let mut queue = ConstructionQueue::default();
+        let mut credits = 1000;
+        queue.push(Project::Mine, 0, &mut credits).unwrap();
+        queue.push(Project::Station, 2, &mut credits).unwrap();
+        queue
+    }
+
+    fn projects(queue: &ConstructionQueue) -> Vec<Project> {
+        queue.items().iter().map(|item| item.project).collect()
+    }
+
+    #[test]
+    fn push_pays_up_front() {
+        let mut queue = ConstructionQueue::default();
+        let mut credits = 100;
+        queue.push(Project::Mine, 1, &mut credits).unwrap();
+        assert_eq!(credits, 100 - Project::Mine.cost());
+        assert_eq!(
+            queue.items(),
+            &[QueueItem {
+                project: Project::Mine,
+                target: 1,
+                turns_left: Project::Mine.build_turns(),
+            }]
+        );
+    }
+
+    #[test]
+    fn push_without_enough_credits_changes_nothing() {
+        let mut queue = ConstructionQueue::default();
+        let mut credits = Project::Station.cost() - 1;
+        assert!(queue.push(Project::Station, 0, &mut credits).is_err());
+        assert_eq!(credits, Project::Station.cost() - 1);
+        assert!(queue.items().is_empty());
+    }
+
+    #[test]
+    fn cancelling_before_work_starts_refunds_everything() {
+        let mut queue = queue();
+        assert_eq!(queue.cancel(1), Project::Station.cost());
+        assert_eq!(projects(&queue), vec![Project::Mine]);
+    }
+
+    #[test]
+    fn cancelling_after_work_starts_refunds_the_work_left() {
+        let mut queue = queue();
+        queue.advance();
+        // A mine is 30 credits over 3 turns, so with 2 turns left 20 credits come back
+        assert_eq!(queue.cancel(0), 20);
+        assert_eq!(projects(&queue), vec![Project::Station]);
+    }
+
+    #[test]
+    fn cancelling_past_the_end_does_nothing() {
+        let mut queue = queue();
+        assert_eq!(queue.cancel(2), 0);
+        assert_eq!(queue.items().len(), 2);
+    }
+
+    #[test]
+    fn moves_swap_neighbours_and_stop_at_the_ends() {
+        let mut queue = queue();
+        queue.move_up(0);
+        queue.move_down(1);
+        assert_eq!(projects(&queue), vec![Project::Mine, Project::Station]);
+        queue.move_up(1);
+        assert_eq!(projects(&queue), vec![Project::Station, Project::Mine]);
+        queue.move_down(0);
+        assert_eq!(projects(&queue), vec![Project::Mine, Project::Station]);
+    }
+
+    #[test]
+    fn reordering_a_started_project_keeps_its_progress() {
+        let mut queue = queue();
+        queue.advance();
+        queue.move_down(0);
+        assert_eq!(queue.items()[1].project, Project::Mine);
+        assert_eq!(queue.items()[1].turns_left, Project::Mine.build_turns() - 1);
+        assert_eq!(queue.items()[1].refund(), 20);
+
+        // The station is worked on now, and the mine picks up where it left off after
+        for _ in 0..Project::Station.build_turns() - 1 {
+            assert_eq!(queue.advance(), None);
+        }
+        assert_eq!(queue.advance().unwrap().project, Project::Station);
+        assert_eq!(queue.advance(), None);
+        assert_eq!(queue.advance().unwrap().project, Project::Mine);
+        assert!(queue.items().is_empty());
+    }
+
+    #[test]
+    fn advance_finishes_the_front_project_after_its_build_turns() {
+        let mut queue = queue();
+        for _ in 0..Project::Mine.build_turns() - 1 {
+            assert_eq!(queue.advance(), None);
+        }
+        let done = queue.advance().unwrap();
+        assert_eq!(done.project, Project::Mine);
+        assert_eq!(done.turns_left, 0);
+        assert_eq!(queue.items()[0].turns_left, Project::Station.build_turns());
+    }
+
+    #[test]
+    fn advancing_an_empty_queue_does_nothing() {
+        assert_eq!(ConstructionQueue::default().advance(), None);
+    }
+}
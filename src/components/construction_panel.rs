@@ -0,0 +1,376 @@
+//! This module is responsible for defining the construction panel, which shows a colony's construction queue and lets
+//! the user add, reorder and cancel projects. Stations can be built around any body in the colony's system, which is
+//! picked on the panel
+
+use apricot::{app::App, rectangle::Rectangle};
+
+use super::{
+    construction::{Category, Colony, Project},
+    label::{set_color, set_opacity},
+    text_metrics::TextMetrics,
+};
+
+/// Space between the edge of the panel's background and its text, in pixels at a UI scale of 1
+const PADDING: f32 = 10.0;
+/// How opaque the panel's background is
+const BACKGROUND_OPACITY: f32 = 0.9;
+/// Which column project details start at
+const DETAILS_COLUMN: usize = 22;
+/// Which column the buttons to reorder and cancel queued projects start at, leaving room for "99 turns left" and a gap
+const QUEUE_BUTTONS_COLUMN: usize = DETAILS_COLUMN + 15;
+/// The color of text that can be clicked, when it's hovered
+const HOVERED_COLOR: nalgebra_glm::Vec3 = nalgebra_glm::Vec3::new(0.94, 0.86, 0.35);
+/// The color of text that's just for information
+const FADED_COLOR: nalgebra_glm::Vec3 = nalgebra_glm::Vec3::new(0.6, 0.6, 0.6);
+/// The color of everything else
+const TEXT_COLOR: nalgebra_glm::Vec3 = nalgebra_glm::Vec3::new(1.0, 1.0, 1.0);
+
+/// What the user picked on the construction panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstructionAction {
+    /// Add a project to the back of the queue, built at the `i`th body of the system
+    Build(Project, usize),
+    /// Build stations around the `i`th body of the system
+    StationTarget(usize),
+    /// Move the `i`th project in the queue one place forward
+    MoveUp(usize),
+    /// Move the `i`th project in the queue one place back
+    MoveDown(usize),
+    /// Take the `i`th project out of the queue, for a refund
+    Cancel(usize),
+    Close,
+}
+
+/// A piece of text on one of the panel's lines, which may be clickable
+struct Segment {
+    /// How many characters in from the left of the line the text starts
+    column: usize,
+    text: String,
+    /// What happens when the text is clicked, if anything
+    action: Option<ConstructionAction>,
+    /// Whether the text is drawn faded out, for less important details
+    faded: bool,
+}
+
+impl Segment {
+    fn text(column: usize, text: String) -> Self {
+        Self {
+            column,
+            text,
+            action: None,
+            faded: false,
+        }
+    }
+
+    fn faded(column: usize, text: String) -> Self {
+        Self {
+            faded: true,
+            ..Self::text(column, text)
+        }
+    }
+
+    fn button(column: usize, text: String, action: ConstructionAction) -> Self {
+        Self {
+            action: Some(action),
+            ..Self::text(column, text)
+        }
+    }
+}
+
+/// Panel in the middle of the screen that shows the construction queue of the selected colony
+pub struct ConstructionPanel {
+    /// The center of the window, which the panel is centered on
+    center: nalgebra_glm::Vec2,
+    /// Whether the panel is shown
+    pub open: bool,
+    /// The lines of the panel, rebuilt every update
+    lines: Vec<Vec<Segment>>,
+    /// Why the last project couldn't be added, if it couldn't
+    message: Option<String>,
+    /// Index of the body that stations are built around, or None for the colony's own body
+    station_target: Option<usize>,
+    /// The size of the font the panel's text is laid out with
+    metrics: TextMetrics,
+}
+
+impl ConstructionPanel {
+    /// Creates a new, closed construction panel
+    pub fn new(center: nalgebra_glm::Vec2, metrics: TextMetrics) -> Self {
+        Self {
+            center,
+            open: false,
+            lines: vec![],
+            message: None,
+            station_target: None,
+            metrics,
+        }
+    }
+
+    /// Moves the panel, for when the window is resized
+    pub fn set_center(&mut self, center: nalgebra_glm::Vec2) {
+        self.center = center;
+    }
+
+    /// Shows why the last project couldn't be added, or clears the message if None
+    pub fn set_message(&mut self, message: Option<String>) {
+        self.message = message;
+    }
+
+    /// Rebuilds the panel for a colony, and checks if anything is clicked. `bodies` are the names of the natural
+    /// bodies in the colony's system, in spawn order. Closing and picking where stations go are handled here
+    pub fn update(
+        &mut self,
+        app: &App,
+        colony: &Colony,
+        bodies: &[String],
+        credits: u32,
+        ships: usize,
+    ) -> Option<ConstructionAction> {
+        self.lines = self.build_lines(colony, bodies, credits, ships);
+        if !app.mouse_left_clicked {
+            return None;
+        }
+        let action = self.hovered_action(app)?;
+        match action {
+            ConstructionAction::Close => {
+                self.open = false;
+                self.message = None;
+                self.station_target = None;
+            }
+            ConstructionAction::StationTarget(target) => self.station_target = Some(target),
+            _ => {}
+        }
+        Some(action)
+    }
+
+    /// Renders the panel to the screen, on a background
+    pub fn render(&self, app: &App, program_2d_id: u32) {
+        let pos = self.top_left();
+        let size = self.size();
+        set_opacity(program_2d_id, BACKGROUND_OPACITY);
+        app.renderer.copy_texture(
+            Rectangle::new(pos.x, pos.y, size.x, size.y),
+            app.renderer.get_texture_id_from_name("panel").unwrap(),
+            Rectangle::new(0.0, 0.0, 8.0, 8.0),
+        );
+        set_opacity(program_2d_id, 1.0);
+
+        for (i, line) in self.lines.iter().enumerate() {
+            for segment in line.iter() {
+                let color = if segment.action.is_some()
+                    && self.segment_rect(i, segment).contains_point(&app.mouse_pos)
+                {
+                    HOVERED_COLOR
+                } else if segment.faded {
+                    FADED_COLOR
+                } else {
+                    TEXT_COLOR
+                };
+                set_color(program_2d_id, color);
+                app.renderer
+                    .draw_text(self.segment_pos(i, segment), segment.text.as_str());
+            }
+        }
+        set_color(program_2d_id, TEXT_COLOR);
+    }
+
+    /// Builds the lines of the panel for a colony
+    fn build_lines(
+        &self,
+        colony: &Colony,
+        bodies: &[String],
+        credits: u32,
+        ships: usize,
+    ) -> Vec<Vec<Segment>> {
+        let body_name = |body: usize| bodies.get(body).map_or("?", |name| name.as_str());
+        let station_target = self
+            .station_target
+            .filter(|target| *target < bodies.len())
+            .unwrap_or(colony.body);
+        let mut lines = vec![
+            vec![Segment::text(0, format!("construction - {}", colony.name))],
+            vec![Segment::text(
+                0,
+                format!("credits: {} (+{} a turn)", credits, colony.income()),
+            )],
+            vec![Segment::text(
+                0,
+                format!(
+                    "population: {:.1} of {:.0} million (habitability {:.0}%)",
+                    colony.population,
+                    colony.population_capacity(),
+                    colony.habitability * 100.0
+                ),
+            )],
+            vec![],
+            vec![Segment::text(0, "build:".to_string())],
+        ];
+        for project in Project::ALL {
+            let details = match project.requirement(colony) {
+                Some(requirement) => requirement.to_string(),
+                None => format!(
+                    "{} credits, {} turns",
+                    project.cost(),
+                    project.build_turns()
+                ),
+            };
+            let target = if project.category() == Category::Station {
+                station_target
+            } else {
+                colony.body
+            };
+            lines.push(vec![
+                Segment::button(
+                    0,
+                    format!("+ {}", project.name()),
+                    ConstructionAction::Build(project, target),
+                ),
+                Segment::faded(DETAILS_COLUMN, details),
+            ]);
+            if project.category() == Category::Station && !bodies.is_empty() {
+                let previous = (station_target + bodies.len() - 1) % bodies.len();
+                let next = (station_target + 1) % bodies.len();
+                let name = body_name(station_target);
+                lines.push(vec![
+                    Segment::faded(2, "around:".to_string()),
+                    Segment::button(
+                        10,
+                        "<".to_string(),
+                        ConstructionAction::StationTarget(previous),
+                    ),
+                    Segment::text(12, name.to_string()),
+                    Segment::button(
+                        13 + name.len(),
+                        ">".to_string(),
+                        ConstructionAction::StationTarget(next),
+                    ),
+                ]);
+            }
+        }
+
+        lines.push(vec![]);
+        lines.push(vec![Segment::text(0, "queue:".to_string())]);
+        if colony.queue.items().is_empty() {
+            lines.push(vec![Segment::faded(0, "nothing queued".to_string())]);
+        }
+        for (i, item) in colony.queue.items().iter().enumerate() {
+            lines.push(vec![
+                Segment::text(0, format!("{}. {}", i + 1, item.project.name())),
+                Segment::faded(DETAILS_COLUMN, format!("{} turns left", item.turns_left)),
+                Segment::button(
+                    QUEUE_BUTTONS_COLUMN,
+                    "up".to_string(),
+                    ConstructionAction::MoveUp(i),
+                ),
+                Segment::button(
+                    QUEUE_BUTTONS_COLUMN + 3,
+                    "down".to_string(),
+                    ConstructionAction::MoveDown(i),
+                ),
+                Segment::button(
+                    QUEUE_BUTTONS_COLUMN + 8,
+                    format!("cancel (+{})", item.refund()),
+                    ConstructionAction::Cancel(i),
+                ),
+            ]);
+            if item.project.category() == Category::Station {
+                lines.push(vec![Segment::faded(
+                    3,
+                    format!("around {}", body_name(item.target)),
+                )]);
+            }
+        }
+
+        lines.push(vec![]);
+        let buildings = if colony.buildings.is_empty() {
+            "none".to_string()
+        } else {
+            colony
+                .buildings
+                .iter()
+                .map(|building| building.name())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        lines.push(vec![Segment::text(0, format!("buildings: {}", buildings))]);
+        let stations = if colony.stations.is_empty() {
+            "none".to_string()
+        } else {
+            colony
+                .stations
+                .iter()
+                .map(|body| format!("around {}", body_name(*body)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        lines.push(vec![Segment::text(
+            0,
+            format!("orbital stations: {}", stations),
+        )]);
+        lines.push(vec![Segment::text(
+            0,
+            format!("ships in system: {}", ships),
+        )]);
+        if let Some(message) = &self.message {
+            lines.push(vec![]);
+            lines.push(vec![Segment::faded(0, message.clone())]);
+        }
+        lines.push(vec![]);
+        lines.push(vec![Segment::button(
+            0,
+            "close".to_string(),
+            ConstructionAction::Close,
+        )]);
+        lines
+    }
+
+    /// What the clickable text under the mouse does, if there is any
+    fn hovered_action(&self, app: &App) -> Option<ConstructionAction> {
+        self.lines.iter().enumerate().find_map(|(i, line)| {
+            line.iter()
+                .filter(|segment| self.segment_rect(i, segment).contains_point(&app.mouse_pos))
+                .find_map(|segment| segment.action)
+        })
+    }
+
+    /// The size of the panel, including its background
+    fn size(&self) -> nalgebra_glm::Vec2 {
+        let columns = self
+            .lines
+            .iter()
+            .flatten()
+            .map(|segment| segment.column + segment.text.len())
+            .max()
+            .unwrap_or(0);
+        let padding = self.metrics.scale(PADDING);
+        nalgebra_glm::vec2(
+            self.metrics.char_width * columns as f32 + padding * 2.0,
+            self.metrics.line_height * self.lines.len() as f32 + padding * 2.0,
+        )
+    }
+
+    /// The top-left corner of the panel
+    fn top_left(&self) -> nalgebra_glm::Vec2 {
+        self.center - self.size() / 2.0
+    }
+
+    /// The top-left corner of a segment on the `i`th line
+    fn segment_pos(&self, i: usize, segment: &Segment) -> nalgebra_glm::Vec2 {
+        self.top_left()
+            + nalgebra_glm::vec2(
+                self.metrics.scale(PADDING) + self.metrics.char_width * segment.column as f32,
+                self.metrics.scale(PADDING) + self.metrics.line_height * i as f32,
+            )
+    }
+
+    /// The rectangle that a segment on the `i`th line covers on the screen
+    fn segment_rect(&self, i: usize, segment: &Segment) -> Rectangle {
+        let pos = self.segment_pos(i, segment);
+        Rectangle::new(
+            pos.x,
+            pos.y,
+            self.metrics.char_width * segment.text.len() as f32,
+            self.metrics.line_height,
+        )
+    }
+}
@@ -0,0 +1,165 @@
+//! This module is responsible for defining the controls screen, which lists what each action is bound to and lets the
+//! user rebind them
+
+use apricot::{app::App, rectangle::Rectangle};
+use sdl2::keyboard::Scancode;
+
+use super::{
+    input::{Action, Binding, InputMap, CONTROLS_PATH},
+    text_metrics::TextMetrics,
+};
+
+/// How many characters wide the action column is, including the gap after it
+const LABEL_COLUMN_WIDTH: usize = 23;
+/// How many characters wide the binding column is, including the gap after it
+const BINDING_COLUMN_WIDTH: usize = 16;
+/// How many characters wide each column of actions is, including the hover marker before the action
+const COLUMN_WIDTH: usize = 2 + LABEL_COLUMN_WIDTH + BINDING_COLUMN_WIDTH;
+/// Shown in place of the binding of the action waiting to be rebound
+const REBINDING_PROMPT: &str = "press a key...";
+
+/// Screen that lists each action's binding. Clicking an action waits for the next key or mouse click, and binds the
+/// action to it
+pub struct ControlsScreen {
+    /// The top-left corner of the screen's text
+    position: nalgebra_glm::Vec2,
+    /// How wide the window is, which decides how many columns of actions fit side by side
+    window_width: f32,
+    /// Whether the screen is shown
+    pub open: bool,
+    /// The action waiting to be rebound, if any
+    rebinding: Option<Action>,
+    /// Why the last rebinding wasn't allowed, if it wasn't
+    error: Option<String>,
+    /// The size of the font the screen's text is laid out with
+    metrics: TextMetrics,
+}
+
+impl ControlsScreen {
+    /// Creates a new, closed controls screen
+    pub fn new(position: nalgebra_glm::Vec2, metrics: TextMetrics) -> Self {
+        Self {
+            position,
+            window_width: 0.0,
+            open: false,
+            rebinding: None,
+            error: None,
+            metrics,
+        }
+    }
+
+    /// Changes how wide the window is, for when the window is resized
+    pub fn set_window_width(&mut self, window_width: f32) {
+        self.window_width = window_width;
+    }
+
+    /// Whether an action is waiting to be rebound
+    pub fn is_rebinding(&self) -> bool {
+        self.rebinding.is_some()
+    }
+
+    /// Rebinds the action waiting to be rebound if a key or the mouse was pressed, otherwise checks if an action is
+    /// clicked. An action already bound to the new key swaps over to the old one. New bindings are saved to the
+    /// controls file right away
+    pub fn update(&mut self, app: &App, input_map: &mut InputMap) {
+        if let Some(action) = self.rebinding {
+            let binding = match input_map.pressed_key() {
+                Some(Scancode::Escape) => {
+                    // Escape cancels rebinding, without binding anything
+                    self.rebinding = None;
+                    return;
+                }
+                Some(scancode) => Binding::Key(scancode),
+                None if app.mouse_left_clicked => Binding::MouseLeft,
+                None => return,
+            };
+            self.rebinding = None;
+            if let Err(err) = input_map.bind(action, binding) {
+                self.error = Some(err);
+                return;
+            }
+            self.error = None;
+            if let Err(err) = input_map.save(CONTROLS_PATH) {
+                println!("could not save controls: {}", err);
+            }
+            return;
+        }
+
+        if !app.mouse_left_clicked {
+            return;
+        }
+        for (i, action) in Action::ALL.iter().enumerate() {
+            if self.line_rect(i).contains_point(&app.mouse_pos) {
+                self.rebinding = Some(*action);
+            }
+        }
+    }
+
+    /// Renders the controls screen to the screen
+    pub fn render(&self, app: &App, input_map: &InputMap) {
+        app.renderer.draw_text(
+            self.position,
+            "controls - click an action to rebind it, escape to cancel",
+        );
+        if let Some(error) = &self.error {
+            app.renderer.draw_text(
+                nalgebra_glm::vec2(self.position.x, self.position.y + self.metrics.line_height),
+                error,
+            );
+        }
+        for (i, action) in Action::ALL.iter().enumerate() {
+            let binding = if self.rebinding == Some(*action) {
+                REBINDING_PROMPT.to_string()
+            } else {
+                input_map.binding(*action).name()
+            };
+            let marker = if self.line_rect(i).contains_point(&app.mouse_pos) {
+                ">"
+            } else {
+                " "
+            };
+            app.renderer.draw_text(
+                self.line_pos(i),
+                format!(
+                    "{} {:width$}{}",
+                    marker,
+                    action.label(),
+                    binding,
+                    width = LABEL_COLUMN_WIDTH
+                )
+                .as_str(),
+            );
+        }
+    }
+
+    /// How many actions are listed in each column before starting the next one. The actions are split evenly between
+    /// as many columns as fit across the window, or listed in one column if even that doesn't fit
+    fn rows_per_column(&self) -> usize {
+        let column_width = self.metrics.char_width * COLUMN_WIDTH as f32;
+        let columns = ((self.window_width - self.position.x) / column_width)
+            .floor()
+            .max(1.0) as usize;
+        Action::ALL.len().div_ceil(columns)
+    }
+
+    /// The top-left corner of the `i`th action's line on the screen, below the title
+    fn line_pos(&self, i: usize) -> nalgebra_glm::Vec2 {
+        let rows_per_column = self.rows_per_column();
+        let (column, row) = (i / rows_per_column, i % rows_per_column);
+        nalgebra_glm::vec2(
+            self.position.x + self.metrics.char_width * (column * COLUMN_WIDTH) as f32,
+            self.position.y + self.metrics.line_height * (row + 2) as f32,
+        )
+    }
+
+    /// The rectangle that the `i`th action's line covers on the screen
+    fn line_rect(&self, i: usize) -> Rectangle {
+        let pos = self.line_pos(i);
+        Rectangle::new(
+            pos.x,
+            pos.y,
+            self.metrics.char_width * COLUMN_WIDTH as f32,
+            self.metrics.line_height,
+        )
+    }
+}
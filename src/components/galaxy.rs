@@ -0,0 +1,769 @@
+//! This module is responsible for generating the galaxy: many star systems placed in a galaxy shape, connected by a
+//! graph of hyperlanes. Only the bodies of the system being looked at live in the World, each system's bodies are
+//! spawned when it is entered.
+
+use std::f32::consts::PI;
+
+use apricot::{bvh::BVH, render_core::RenderContext};
+use hecs::{Entity, World};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::{
+    calendar::DAYS_PER_YEAR,
+    habitability::{Atmosphere, Environment, Surface},
+    planet::Planet,
+    star::Star,
+};
+
+/// How far the edge of the galaxy is from its center, in galaxy map units
+const GALAXY_RADIUS: f32 = 1000.0;
+/// How close two star systems can be, in galaxy map units
+const MIN_SYSTEM_SEPARATION: f32 = 40.0;
+/// How many of its nearest neighbors each system gets a hyperlane to, on top of the lanes keeping the galaxy connected
+const NEAREST_LANES: usize = 2;
+/// How far earth orbits from the sun, in world units. Procedural planets are placed relative to this
+const AU: f32 = 20000.0;
+/// Syllables that system names are made from
+const NAME_SYLLABLES: [&str; 16] = [
+    "al", "be", "cor", "da", "el", "fa", "gor", "hy", "ix", "ka", "lo", "mi", "nor", "pha", "ro",
+    "tau",
+];
+/// Numerals for naming planets after their star
+const NUMERALS: [&str; 8] = ["I", "II", "III", "IV", "V", "VI", "VII", "VIII"];
+/// Letters for naming the stars of systems with more than one
+const STAR_LETTERS: [&str; 3] = ["A", "B", "C"];
+/// How many arms spiral galaxies have
+const SPIRAL_ARMS: u32 = 3;
+
+/// The overall shape that star systems are scattered in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GalaxyShape {
+    /// Systems trail out from the core along a few curved arms
+    Spiral { arms: u32 },
+    /// Systems are clumped around the core in an oval
+    Elliptical,
+    /// Systems form a band around an empty core
+    Ring,
+}
+
+impl GalaxyShape {
+    /// Every shape, in the order they're picked from on the options screen
+    pub const ALL: [GalaxyShape; 3] = [
+        GalaxyShape::Spiral { arms: SPIRAL_ARMS },
+        GalaxyShape::Elliptical,
+        GalaxyShape::Ring,
+    ];
+
+    /// The name of the shape, for the options screen
+    pub fn name(&self) -> &'static str {
+        match self {
+            GalaxyShape::Spiral { .. } => "spiral",
+            GalaxyShape::Elliptical => "elliptical",
+            GalaxyShape::Ring => "ring",
+        }
+    }
+}
+
+/// A single star system on the galaxy map
+pub struct StarSystem {
+    pub name: String,
+    /// Where the system is on the galaxy map
+    pub position: nalgebra_glm::Vec3,
+    /// Seed for procedurally generating the system's bodies, so they're the same every time it is entered
+    seed: u64,
+}
+
+/// Every star system, and the hyperlanes between them
+pub struct Galaxy {
+    pub systems: Vec<StarSystem>,
+    /// Pairs of indices into `systems` that are connected by a hyperlane
+    pub lanes: Vec<(usize, usize)>,
+    /// Index into `systems` of the player's home system, which is always the sun's system
+    pub home: usize,
+    /// Index into `systems` of Alpha Centauri, a hand-built binary system next to home, if there's room for it
+    pub alpha_centauri: Option<usize>,
+}
+
+impl Galaxy {
+    /// Procedurally generates a galaxy of `system_count` systems. There's always at least the home system
+    pub fn generate(shape: GalaxyShape, system_count: usize, seed: u64) -> Self {
+        let system_count = system_count.max(1);
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let mut positions: Vec<nalgebra_glm::Vec3> = vec![];
+        let mut attempts = 0;
+        while positions.len() < system_count && attempts < system_count * 100 {
+            attempts += 1;
+            let position = random_position(&mut rng, shape);
+            if positions
+                .iter()
+                .all(|other| nalgebra_glm::distance(other, &position) >= MIN_SYSTEM_SEPARATION)
+            {
+                positions.push(position);
+            }
+        }
+
+        let mut systems: Vec<StarSystem> = positions
+            .into_iter()
+            .map(|position| StarSystem {
+                name: random_name(&mut rng),
+                position,
+                seed: rng.gen(),
+            })
+            .collect();
+        let home = 0;
+        systems[home].name = "Sol".to_string();
+        let home_position = systems[home].position;
+        let alpha_centauri = (0..systems.len())
+            .filter(|system| *system != home)
+            .min_by(|a, b| {
+                let distance = |system: &usize| {
+                    nalgebra_glm::distance(&systems[*system].position, &home_position)
+                };
+                distance(a).total_cmp(&distance(b))
+            });
+        if let Some(alpha_centauri) = alpha_centauri {
+            systems[alpha_centauri].name = "Alpha Centauri".to_string();
+        }
+
+        let lanes = generate_lanes(&systems);
+        Self {
+            systems,
+            lanes,
+            home,
+            alpha_centauri,
+        }
+    }
+
+    /// The systems a system has a hyperlane to
+    pub fn neighbors(&self, system: usize) -> impl Iterator<Item = usize> + '_ {
+        self.lanes.iter().filter_map(move |(a, b)| {
+            if *a == system {
+                Some(*b)
+            } else if *b == system {
+                Some(*a)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Spawns the bodies of a system into the world, returning them with the star first. Orbit lines are drawn with
+    /// `orbit_segments` line segments
+    pub fn spawn_system(
+        &self,
+        system: usize,
+        world: &mut World,
+        renderer: &RenderContext,
+        bvh: &mut BVH<Entity>,
+        orbit_segments: usize,
+    ) -> Vec<Entity> {
+        if system == self.home {
+            spawn_sol(world, renderer, bvh, orbit_segments)
+        } else if Some(system) == self.alpha_centauri {
+            spawn_alpha_centauri(world, renderer, bvh, orbit_segments)
+        } else {
+            spawn_procedural(&self.systems[system], world, renderer, bvh, orbit_segments)
+        }
+    }
+}
+
+/// Picks a random spot in the galaxy for a star system
+fn random_position(rng: &mut StdRng, shape: GalaxyShape) -> nalgebra_glm::Vec3 {
+    let thickness = rng.gen_range(-1.0..1.0) * GALAXY_RADIUS * 0.03;
+    match shape {
+        GalaxyShape::Spiral { arms } => {
+            let distance = rng.gen::<f32>().sqrt() * GALAXY_RADIUS;
+            let arm = rng.gen_range(0..arms.max(1)) as f32;
+            let angle = arm * 2.0 * PI / arms.max(1) as f32
+                + distance / GALAXY_RADIUS * 2.0 * PI
+                + rng.gen_range(-0.3..0.3);
+            nalgebra_glm::vec3(angle.cos() * distance, angle.sin() * distance, thickness)
+        }
+        GalaxyShape::Elliptical => {
+            // Averaging a few random numbers clumps systems toward the core
+            let mut clumped = || (0..3).map(|_| rng.gen_range(-1.0..1.0)).sum::<f32>() / 3.0;
+            nalgebra_glm::vec3(
+                clumped() * GALAXY_RADIUS,
+                clumped() * GALAXY_RADIUS * 0.6,
+                thickness,
+            )
+        }
+        GalaxyShape::Ring => {
+            let distance = GALAXY_RADIUS * rng.gen_range(0.7..1.0);
+            let angle = rng.gen_range(0.0..2.0 * PI);
+            nalgebra_glm::vec3(angle.cos() * distance, angle.sin() * distance, thickness)
+        }
+    }
+}
+
+/// Makes up a name for a star system
+fn random_name(rng: &mut StdRng) -> String {
+    let syllables = rng.gen_range(2..4);
+    let name: String = (0..syllables)
+        .map(|_| NAME_SYLLABLES[rng.gen_range(0..NAME_SYLLABLES.len())])
+        .collect();
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => name,
+    }
+}
+
+/// Connects systems with hyperlanes. A minimum spanning tree makes sure every system can be reached, and each system
+/// is also connected to its nearest neighbors so there are alternate routes
+fn generate_lanes(systems: &[StarSystem]) -> Vec<(usize, usize)> {
+    let distance =
+        |a: usize, b: usize| nalgebra_glm::distance(&systems[a].position, &systems[b].position);
+    let mut lanes: Vec<(usize, usize)> = vec![];
+    let add_lane = |lanes: &mut Vec<(usize, usize)>, a: usize, b: usize| {
+        let lane = (a.min(b), a.max(b));
+        if !lanes.contains(&lane) {
+            lanes.push(lane);
+        }
+    };
+
+    // Prim's algorithm
+    let mut in_tree = vec![false; systems.len()];
+    let mut closest: Vec<(f32, usize)> = vec![(f32::MAX, 0); systems.len()];
+    if !systems.is_empty() {
+        closest[0] = (0.0, 0);
+    }
+    for _ in 0..systems.len() {
+        let next = (0..systems.len())
+            .filter(|i| !in_tree[*i])
+            .min_by(|a, b| closest[*a].0.total_cmp(&closest[*b].0))
+            .unwrap();
+        in_tree[next] = true;
+        if next != closest[next].1 {
+            add_lane(&mut lanes, next, closest[next].1);
+        }
+        for other in 0..systems.len() {
+            if !in_tree[other] && distance(next, other) < closest[other].0 {
+                closest[other] = (distance(next, other), next);
+            }
+        }
+    }
+
+    for system in 0..systems.len() {
+        let mut others: Vec<usize> = (0..systems.len()).filter(|i| *i != system).collect();
+        others.sort_by(|a, b| distance(system, *a).total_cmp(&distance(system, *b)));
+        for other in others.into_iter().take(NEAREST_LANES) {
+            add_lane(&mut lanes, system, other);
+        }
+    }
+
+    lanes
+}
+
+/// Spawns the sun, and the planets and moons around it
+fn spawn_sol(
+    world: &mut World,
+    renderer: &RenderContext,
+    bvh: &mut BVH<Entity>,
+    orbit_segments: usize,
+) -> Vec<Entity> {
+    let sun_entity = Planet::new(
+        world,
+        renderer,
+        bvh,
+        orbit_segments,
+        true,
+        Entity::DANGLING,
+        0,
+        110.0,
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        renderer.get_texture_id_from_name("sun").unwrap(),
+        "Sun".to_string(),
+    );
+    world
+        .insert_one(
+            sun_entity,
+            Star::new(1.0, nalgebra_glm::vec3(1.0, 1.0, 1.0)),
+        )
+        .unwrap();
+
+    let mercury_entity = Planet::new(
+        world,
+        renderer,
+        bvh,
+        orbit_segments,
+        false,
+        sun_entity,
+        1,
+        0.375,
+        10000.0,
+        0.0,
+        1.0,
+        0.0027,
+        renderer.get_texture_id_from_name("moon").unwrap(),
+        "Mercury".to_string(),
+    );
+    world
+        .insert_one(
+            mercury_entity,
+            Environment::new(0.98, Atmosphere::None, Surface::Barren),
+        )
+        .unwrap();
+
+    let planet_entity = Planet::new(
+        world,
+        renderer,
+        bvh,
+        orbit_segments,
+        false,
+        sun_entity,
+        1,
+        1.,
+        20000.0,
+        0.0,
+        1.0,
+        0.0027,
+        renderer.get_texture_id_from_name("earth").unwrap(),
+        "Earth".to_string(),
+    );
+    world
+        .insert_one(
+            planet_entity,
+            Environment::new(1.0, Atmosphere::Breathable, Surface::Terran),
+        )
+        .unwrap();
+
+    let moon_entity = Planet::new(
+        world,
+        renderer,
+        bvh,
+        orbit_segments,
+        false,
+        planet_entity,
+        2,
+        0.72,
+        60.0,
+        0.0,
+        0.0749,
+        0.0749,
+        renderer.get_texture_id_from_name("moon").unwrap(),
+        "Moon".to_string(),
+    );
+    world
+        .insert_one(
+            moon_entity,
+            Environment::new(0.6, Atmosphere::None, Surface::Barren),
+        )
+        .unwrap();
+
+    vec![sun_entity, mercury_entity, planet_entity, moon_entity]
+}
+
+/// Spawns Alpha Centauri, a hand-built binary system. Each star has a planet of its own, and one planet orbits the
+/// pair
+fn spawn_alpha_centauri(
+    world: &mut World,
+    renderer: &RenderContext,
+    bvh: &mut BVH<Entity>,
+    orbit_segments: usize,
+) -> Vec<Entity> {
+    // The heavier star orbits closer to the barycenter, and both share an orbital period, on opposite sides
+    let stars = [
+        (
+            "Alpha Centauri A",
+            134.0,
+            1.5,
+            nalgebra_glm::vec3(1.0, 0.95, 0.85),
+            AU * 2.0,
+            0.0,
+        ),
+        (
+            "Alpha Centauri B",
+            95.0,
+            0.5,
+            nalgebra_glm::vec3(1.0, 0.8, 0.6),
+            AU * 2.4,
+            PI,
+        ),
+    ];
+    let mut bodies = vec![];
+    for (name, radius, luminosity, color, orbital_radius, orbital_phase) in stars {
+        let star_entity = Planet::new(
+            world,
+            renderer,
+            bvh,
+            orbit_segments,
+            true,
+            Entity::DANGLING,
+            0,
+            radius,
+            orbital_radius,
+            orbital_phase,
+            79.9,
+            0.0,
+            renderer.get_texture_id_from_name("sun").unwrap(),
+            name.to_string(),
+        );
+        world
+            .insert_one(star_entity, Star::new(luminosity, color))
+            .unwrap();
+        bodies.push(star_entity);
+    }
+
+    let planets = [
+        (
+            bodies[0],
+            "Alpha Centauri Ab",
+            1.2,
+            AU * 0.6,
+            Environment::new(1.1, Atmosphere::Thin, Surface::Desert),
+        ),
+        (
+            bodies[1],
+            "Alpha Centauri Bb",
+            0.8,
+            AU * 0.25,
+            Environment::new(0.9, Atmosphere::None, Surface::Volcanic),
+        ),
+        (
+            Entity::DANGLING,
+            "Alpha Centauri I",
+            7.0,
+            AU * 8.0,
+            Environment::new(0.3, Atmosphere::Dense, Surface::Gaseous),
+        ),
+    ];
+    for (parent, name, radius, orbital_radius, environment) in planets {
+        let gaseous = environment.surface == Surface::Gaseous;
+        let planet_entity = Planet::new(
+            world,
+            renderer,
+            bvh,
+            orbit_segments,
+            gaseous,
+            parent,
+            1,
+            radius,
+            orbital_radius,
+            0.0,
+            (orbital_radius / AU).powf(1.5),
+            1.0 / DAYS_PER_YEAR,
+            renderer
+                .get_texture_id_from_name(if gaseous { "earth" } else { "moon" })
+                .unwrap(),
+            name.to_string(),
+        );
+        world.insert_one(planet_entity, environment).unwrap();
+        bodies.push(planet_entity);
+    }
+
+    bodies
+}
+
+/// Spawns one, two or three stars with a random assortment of planets and moons, seeded by the system so it's the
+/// same every time. Multiple stars orbit their barycenter, and planets orbit either a single star or all of them
+fn spawn_procedural(
+    system: &StarSystem,
+    world: &mut World,
+    renderer: &RenderContext,
+    bvh: &mut BVH<Entity>,
+    orbit_segments: usize,
+) -> Vec<Entity> {
+    let mut rng = StdRng::seed_from_u64(system.seed);
+    let mut bodies = vec![];
+
+    let star_count = match rng.gen_range(0..10) {
+        0..=6 => 1,
+        7..=8 => 2,
+        _ => 3,
+    };
+    // Stars are spread evenly around the barycenter, far enough out that planets can orbit a single star
+    let star_orbital_radius = if star_count > 1 {
+        AU * rng.gen_range(1.5..3.0)
+    } else {
+        0.0
+    };
+    let star_orbital_time_years = (star_orbital_radius / AU).powf(1.5);
+    let mut stars = vec![];
+    for i in 0..star_count {
+        let star_radius = rng.gen_range(50.0..180.0);
+        let luminosity = (star_radius / 110.0_f32).powf(2.0);
+        let name = if star_count == 1 {
+            system.name.clone()
+        } else {
+            format!("{} {}", system.name, STAR_LETTERS[i])
+        };
+        let star_entity = Planet::new(
+            world,
+            renderer,
+            bvh,
+            orbit_segments,
+            true,
+            Entity::DANGLING,
+            0,
+            star_radius,
+            star_orbital_radius,
+            2.0 * PI * i as f32 / star_count as f32,
+            star_orbital_time_years,
+            0.0,
+            renderer.get_texture_id_from_name("sun").unwrap(),
+            name.clone(),
+        );
+        world
+            .insert_one(
+                star_entity,
+                Star::new(
+                    luminosity,
+                    nalgebra_glm::vec3(1.0, rng.gen_range(0.7..1.0), rng.gen_range(0.5..1.0)),
+                ),
+            )
+            .unwrap();
+        bodies.push(star_entity);
+        stars.push((star_entity, name));
+    }
+
+    if star_count == 1 {
+        let (star_entity, name) = &stars[0];
+        let planet_count = rng.gen_range(1..=NUMERALS.len());
+        let orbital_radius = AU * rng.gen_range(0.3..0.6);
+        spawn_planets(
+            &mut rng,
+            world,
+            renderer,
+            bvh,
+            orbit_segments,
+            &mut bodies,
+            *star_entity,
+            name,
+            planet_count,
+            orbital_radius,
+        );
+        return bodies;
+    }
+
+    // Planets orbiting a single star stay well inside the stars' orbits, so they aren't pulled away by the others
+    for (star_entity, name) in stars.iter() {
+        let planet_count = rng.gen_range(0..=2);
+        let orbital_radius = star_orbital_radius * rng.gen_range(0.05..0.15);
+        spawn_planets(
+            &mut rng,
+            world,
+            renderer,
+            bvh,
+            orbit_segments,
+            &mut bodies,
+            *star_entity,
+            name,
+            planet_count,
+            orbital_radius,
+        );
+    }
+    // Circumbinary planets orbit the barycenter, well outside the stars' orbits
+    let planet_count = rng.gen_range(1..=4);
+    let orbital_radius = star_orbital_radius * rng.gen_range(3.0..4.0);
+    spawn_planets(
+        &mut rng,
+        world,
+        renderer,
+        bvh,
+        orbit_segments,
+        &mut bodies,
+        Entity::DANGLING,
+        &system.name,
+        planet_count,
+        orbital_radius,
+    );
+
+    bodies
+}
+
+/// Spawns `planet_count` random planets and their moons around a parent, or around the barycenter if the parent is
+/// `Entity::DANGLING`. Each planet orbits further out than the last, starting at `orbital_radius`
+fn spawn_planets(
+    rng: &mut StdRng,
+    world: &mut World,
+    renderer: &RenderContext,
+    bvh: &mut BVH<Entity>,
+    orbit_segments: usize,
+    bodies: &mut Vec<Entity>,
+    parent: Entity,
+    parent_name: &str,
+    planet_count: usize,
+    mut orbital_radius: f32,
+) {
+    for numeral in NUMERALS.iter().take(planet_count) {
+        let gaseous = rng.gen_bool(0.3);
+        let body_radius = if gaseous {
+            rng.gen_range(3.0..11.0)
+        } else {
+            rng.gen_range(0.3..1.8)
+        };
+        // Kepler's third law, in years and AU
+        let orbital_time_years = (orbital_radius / AU).powf(1.5);
+        let name = format!("{} {}", parent_name, numeral);
+        let planet_entity = Planet::new(
+            world,
+            renderer,
+            bvh,
+            orbit_segments,
+            gaseous,
+            parent,
+            1,
+            body_radius,
+            orbital_radius,
+            rng.gen_range(0.0..2.0 * PI),
+            orbital_time_years,
+            rng.gen_range(0.3..3.0) / DAYS_PER_YEAR,
+            renderer
+                .get_texture_id_from_name(if gaseous || rng.gen_bool(0.5) {
+                    "earth"
+                } else {
+                    "moon"
+                })
+                .unwrap(),
+            name.clone(),
+        );
+        world
+            .insert_one(planet_entity, random_environment(rng, gaseous))
+            .unwrap();
+        bodies.push(planet_entity);
+
+        let moon_count = rng.gen_range(0..if gaseous { 4 } else { 2 });
+        for moon in 0..moon_count {
+            let moon_radius = body_radius * rng.gen_range(0.1..0.4);
+            let moon_orbital_radius = body_radius * rng.gen_range(20.0..80.0) * (moon + 1) as f32;
+            let moon_orbital_time_years = rng.gen_range(0.02..0.2);
+            let moon_entity = Planet::new(
+                world,
+                renderer,
+                bvh,
+                orbit_segments,
+                false,
+                planet_entity,
+                2,
+                moon_radius,
+                moon_orbital_radius,
+                rng.gen_range(0.0..2.0 * PI),
+                moon_orbital_time_years,
+                moon_orbital_time_years,
+                renderer.get_texture_id_from_name("moon").unwrap(),
+                format!("{} {}", name, (b'a' + moon as u8) as char),
+            );
+            world
+                .insert_one(moon_entity, random_environment(rng, false))
+                .unwrap();
+            bodies.push(moon_entity);
+        }
+
+        orbital_radius *= rng.gen_range(1.4..2.0);
+    }
+}
+
+/// Makes up an environment for a procedurally generated body
+fn random_environment(rng: &mut StdRng, gaseous: bool) -> Environment {
+    if gaseous {
+        return Environment::new(rng.gen_range(0.2..0.4), Atmosphere::Dense, Surface::Gaseous);
+    }
+    const ATMOSPHERES: [Atmosphere; 5] = [
+        Atmosphere::None,
+        Atmosphere::Thin,
+        Atmosphere::Breathable,
+        Atmosphere::Dense,
+        Atmosphere::Toxic,
+    ];
+    const SURFACES: [Surface; 6] = [
+        Surface::Terran,
+        Surface::Ocean,
+        Surface::Desert,
+        Surface::Arctic,
+        Surface::Barren,
+        Surface::Volcanic,
+    ];
+    Environment::new(
+        rng.gen_range(0.5..1.3),
+        ATMOSPHERES[rng.gen_range(0..ATMOSPHERES.len())],
+        SURFACES[rng.gen_range(0..SURFACES.len())],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Which systems can be reached from the home system over hyperlanes
+    fn reachable(galaxy: &Galaxy) -> Vec<bool> {
+        let mut seen = vec![false; galaxy.systems.len()];
+        let mut frontier = vec![galaxy.home];
+        seen[galaxy.home] = true;
+        while let Some(system) = frontier.pop() {
+            for neighbor in galaxy.neighbors(system) {
+                if !seen[neighbor] {
+                    seen[neighbor] = true;
+                    frontier.push(neighbor);
+                }
+            }
+        }
+        seen
+    }
+
+    #[test]
+    fn same_seed_makes_the_same_galaxy() {
+        for shape in GalaxyShape::ALL {
+            let a = Galaxy::generate(shape, 30, 7);
+            let b = Galaxy::generate(shape, 30, 7);
+            assert_eq!(a.systems.len(), b.systems.len());
+            for (a, b) in a.systems.iter().zip(b.systems.iter()) {
+                assert_eq!(a.name, b.name);
+                assert_eq!(a.position, b.position);
+                assert_eq!(a.seed, b.seed);
+            }
+            assert_eq!(a.lanes, b.lanes);
+            assert_eq!(a.alpha_centauri, b.alpha_centauri);
+        }
+    }
+
+    #[test]
+    fn different_seeds_make_different_galaxies() {
+        let a = Galaxy::generate(GalaxyShape::ALL[0], 30, 7);
+        let b = Galaxy::generate(GalaxyShape::ALL[0], 30, 8);
+        assert_ne!(a.systems[1].position, b.systems[1].position);
+    }
+
+    #[test]
+    fn every_system_is_reachable_over_hyperlanes() {
+        for shape in GalaxyShape::ALL {
+            for seed in 0..5 {
+                let galaxy = Galaxy::generate(shape, 40, seed);
+                assert_eq!(galaxy.systems.len(), 40, "{:?}", shape);
+                assert!(reachable(&galaxy).iter().all(|seen| *seen), "{:?}", shape);
+            }
+        }
+    }
+
+    #[test]
+    fn home_is_sol() {
+        let galaxy = Galaxy::generate(GalaxyShape::ALL[0], 40, 1984);
+        assert_eq!(galaxy.systems[galaxy.home].name, "Sol");
+        let alpha_centauri = galaxy.alpha_centauri.unwrap();
+        assert_ne!(alpha_centauri, galaxy.home);
+        assert_eq!(galaxy.systems[alpha_centauri].name, "Alpha Centauri");
+    }
+
+    #[test]
+    fn empty_galaxy_still_has_home() {
+        let galaxy = Galaxy::generate(GalaxyShape::Ring, 0, 3);
+        assert_eq!(galaxy.systems.len(), 1);
+        assert_eq!(galaxy.systems[galaxy.home].name, "Sol");
+        assert_eq!(galaxy.alpha_centauri, None);
+        assert!(galaxy.lanes.is_empty());
+    }
+
+    #[test]
+    fn systems_keep_their_distance() {
+        let galaxy = Galaxy::generate(GalaxyShape::Elliptical, 40, 2);
+        for (i, a) in galaxy.systems.iter().enumerate() {
+            for b in galaxy.systems[i + 1..].iter() {
+                assert!(nalgebra_glm::distance(&a.position, &b.position) >= MIN_SYSTEM_SEPARATION);
+            }
+        }
+    }
+}
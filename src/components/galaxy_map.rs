@@ -0,0 +1,240 @@
+//! This module is responsible for defining the galaxy map view, which shows every star system and the hyperlanes
+//! between them, and lets the user pick a system to enter
+
+use std::{f32::consts::PI, sync::Arc};
+
+use apricot::{app::App, rectangle::Rectangle, render_core::TextureId};
+
+use super::{
+    button::{Event, EventQueue},
+    galaxy::Galaxy,
+    input::{Action, InputMap},
+    pathfinding::Route,
+    projection::ScreenProjection,
+    text_metrics::TextMetrics,
+};
+
+/// How big star systems are drawn on the map, in pixels at a UI scale of 1
+const SYSTEM_ICON_RADIUS: f32 = 5.0;
+/// How big the dots that make up hyperlanes are drawn, in pixels at a UI scale of 1
+const LANE_DOT_RADIUS: f32 = 1.0;
+/// How far apart the dots that make up hyperlanes are, in pixels at a UI scale of 1
+const LANE_DOT_SPACING: f32 = 8.0;
+/// How big the dots that make up a planned route are drawn, in pixels at a UI scale of 1
+const ROUTE_DOT_RADIUS: f32 = 2.0;
+/// How far apart the dots that make up a planned route are, in pixels at a UI scale of 1
+const ROUTE_DOT_SPACING: f32 = 4.0;
+/// Vertical field of view of the galaxy map camera, in radians
+const CAMERA_FOV: f32 = 0.65;
+/// The size of the icon texture, in pixels
+const ICON_SIZE: f32 = 64.0;
+/// How close the camera can get to the center of the galaxy map
+const MIN_DISTANCE: f32 = 100.0;
+/// How far the camera can get from the center of the galaxy map
+const MAX_DISTANCE: f32 = 5000.0;
+
+/// The galaxy map view, with its own camera that swivels around the galaxy's center
+pub struct GalaxyMap {
+    /// Up-down view angle
+    phi: f32,
+    /// Side-side view angle
+    theta: f32,
+    /// How far the camera is from the center of the galaxy
+    distance: f32,
+    /// Snapshot of the galaxy map camera, for drawing systems
+    projection: ScreenProjection,
+    /// Index of the system under the mouse, if any
+    hovered: Option<usize>,
+    /// The size of the font the map's text is laid out with
+    metrics: TextMetrics,
+    /// The event queue that stores events triggered by this view
+    event_queue: Arc<EventQueue>,
+}
+
+impl GalaxyMap {
+    /// Creates a new galaxy map, looking down at the whole galaxy
+    pub fn new(app: &App, metrics: TextMetrics, event_queue: Arc<EventQueue>) -> Self {
+        let (phi, theta, distance) = (0.0, -1.2, 2500.0);
+        Self {
+            phi,
+            theta,
+            distance,
+            projection: Self::build_projection(app, phi, theta, distance),
+            hovered: None,
+            metrics,
+            event_queue,
+        }
+    }
+
+    /// Swivels and zooms the camera, and checks if a system is clicked. Zooming in past the closest the camera can get
+    /// enters the system under the mouse, or goes back to `current` if there isn't one. `sensitivity` is how far the
+    /// camera swivels for each pixel the mouse moves, in radians
+    pub fn update(
+        &mut self,
+        app: &App,
+        input: &InputMap,
+        galaxy: &Galaxy,
+        current: usize,
+        sensitivity: f32,
+    ) {
+        let control_speed = sensitivity;
+        if input.is_down(Action::Rotate) {
+            self.phi -= control_speed * (app.mouse_vel.x as f32);
+            self.theta = (self.theta - control_speed * (app.mouse_vel.y as f32))
+                .max(control_speed - PI / 2.0)
+                .min(PI / 2.0 - control_speed);
+        }
+        let zoom = input.zoom(app);
+        let zoomed_past_min = zoom > 0.0 && self.distance <= MIN_DISTANCE;
+        self.distance =
+            (self.distance - 0.15 * self.distance * zoom).clamp(MIN_DISTANCE, MAX_DISTANCE);
+        self.projection = Self::build_projection(app, self.phi, self.theta, self.distance);
+
+        self.hovered = galaxy.systems.iter().position(|system| {
+            self.projection
+                .project(system.position)
+                .is_some_and(|screen_pos| {
+                    icon_rect(screen_pos, self.metrics.scale(SYSTEM_ICON_RADIUS) * 2.0)
+                        .contains_point(&app.mouse_pos)
+                })
+        });
+        if app.mouse_left_clicked {
+            if let Some(system) = self.hovered {
+                self.event_queue.push(Event::SystemClicked(system));
+            }
+        } else if zoomed_past_min {
+            self.event_queue
+                .push(Event::SystemClicked(self.hovered.unwrap_or(current)));
+        }
+    }
+
+    /// The system under the mouse, if any
+    pub fn hovered(&self) -> Option<usize> {
+        self.hovered
+    }
+
+    /// Draws every system and hyperlane, highlighting the system currently entered and the planned route, if any
+    pub fn render(
+        &self,
+        app: &App,
+        galaxy: &Galaxy,
+        current: usize,
+        route: Option<&Route>,
+        icon_texture_id: TextureId,
+    ) {
+        for (a, b) in galaxy.lanes.iter() {
+            self.draw_lane(
+                app,
+                galaxy,
+                (*a, *b),
+                self.metrics.scale(LANE_DOT_RADIUS),
+                self.metrics.scale(LANE_DOT_SPACING),
+                icon_texture_id,
+            );
+        }
+
+        if let Some(route) = route {
+            for leg in route.systems.windows(2) {
+                self.draw_lane(
+                    app,
+                    galaxy,
+                    (leg[0], leg[1]),
+                    self.metrics.scale(ROUTE_DOT_RADIUS),
+                    self.metrics.scale(ROUTE_DOT_SPACING),
+                    icon_texture_id,
+                );
+            }
+            let destination = galaxy.systems[*route.systems.last().unwrap()].position;
+            if let Some(screen_pos) = self.projection.project(destination) {
+                app.renderer.draw_text(
+                    screen_pos
+                        + nalgebra_glm::vec2(
+                            self.metrics.scale(SYSTEM_ICON_RADIUS) + self.metrics.char_width * 0.5,
+                            self.metrics.line_height * 0.5,
+                        ),
+                    format!("eta: {} turns", route.eta()).as_str(),
+                );
+            }
+        }
+
+        for (i, system) in galaxy.systems.iter().enumerate() {
+            let Some(screen_pos) = self.projection.project(system.position) else {
+                continue;
+            };
+            let radius = if i == current {
+                self.metrics.scale(SYSTEM_ICON_RADIUS) * 1.6
+            } else {
+                self.metrics.scale(SYSTEM_ICON_RADIUS)
+            };
+            draw_icon(app, screen_pos, radius, icon_texture_id);
+            let name = if i == current {
+                format!("> {}", system.name)
+            } else {
+                system.name.clone()
+            };
+            app.renderer.draw_text(
+                screen_pos
+                    + nalgebra_glm::vec2(
+                        radius + self.metrics.char_width * 0.5,
+                        -self.metrics.line_height * 0.5,
+                    ),
+                name.as_str(),
+            );
+        }
+    }
+
+    /// Draws a dotted line along the hyperlane between two systems
+    fn draw_lane(
+        &self,
+        app: &App,
+        galaxy: &Galaxy,
+        (a, b): (usize, usize),
+        dot_radius: f32,
+        dot_spacing: f32,
+        icon_texture_id: TextureId,
+    ) {
+        let (Some(start), Some(end)) = (
+            self.projection.project(galaxy.systems[a].position),
+            self.projection.project(galaxy.systems[b].position),
+        ) else {
+            return;
+        };
+        let dots = (nalgebra_glm::distance(&start, &end) / dot_spacing) as usize;
+        for dot in 1..dots {
+            let pos = nalgebra_glm::lerp(&start, &end, dot as f32 / dots as f32);
+            draw_icon(app, pos, dot_radius, icon_texture_id);
+        }
+    }
+
+    /// Builds the projection for a camera swiveled around the center of the galaxy
+    fn build_projection(app: &App, phi: f32, theta: f32, distance: f32) -> ScreenProjection {
+        let rot_matrix =
+            nalgebra_glm::rotate_y(&nalgebra_glm::rotate_z(&nalgebra_glm::one(), phi), theta);
+        ScreenProjection::new(
+            (rot_matrix * nalgebra_glm::vec4(distance, 0., 0., 0.)).xyz(),
+            nalgebra_glm::vec3(0.0, 0.0, 0.0),
+            CAMERA_FOV,
+            MAX_DISTANCE * 4.0,
+            nalgebra_glm::vec2(app.window_size.x as f32, app.window_size.y as f32),
+        )
+    }
+}
+
+/// The square on the screen covered by an icon centered at `center`
+fn icon_rect(center: nalgebra_glm::Vec2, radius: f32) -> Rectangle {
+    Rectangle::new(
+        center.x - radius,
+        center.y - radius,
+        radius * 2.0,
+        radius * 2.0,
+    )
+}
+
+/// Draws the round icon texture centered at `center`
+fn draw_icon(app: &App, center: nalgebra_glm::Vec2, radius: f32, icon_texture_id: TextureId) {
+    app.renderer.copy_texture(
+        icon_rect(center, radius),
+        icon_texture_id,
+        Rectangle::new(0.0, 0.0, ICON_SIZE, ICON_SIZE),
+    );
+}
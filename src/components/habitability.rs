@@ -0,0 +1,238 @@
+//! This module is responsible for defining the physical environment of bodies, and how habitable that makes them
+
+use hecs::{Entity, Without, World};
+
+use super::{planet::Planet, star::Star};
+
+/// How far earth orbits from the sun, in world units. Insolation is measured relative to this distance
+const AU: f32 = 20000.0;
+
+/// What kind of atmosphere a body has
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Atmosphere {
+    None,
+    Thin,
+    Breathable,
+    Dense,
+    Toxic,
+}
+
+impl Atmosphere {
+    /// How suitable the atmosphere is for life, from 0 to 1
+    fn suitability(&self) -> f32 {
+        match self {
+            Atmosphere::None => 0.1,
+            Atmosphere::Thin => 0.5,
+            Atmosphere::Breathable => 1.0,
+            Atmosphere::Dense => 0.4,
+            Atmosphere::Toxic => 0.2,
+        }
+    }
+}
+
+/// What a body's surface is mostly made of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Surface {
+    Terran,
+    Ocean,
+    Desert,
+    Arctic,
+    Barren,
+    Volcanic,
+    Gaseous,
+}
+
+impl Surface {
+    /// How suitable the surface is for life, from 0 to 1
+    fn suitability(&self) -> f32 {
+        match self {
+            Surface::Terran => 1.0,
+            Surface::Ocean => 0.8,
+            Surface::Desert => 0.5,
+            Surface::Arctic => 0.4,
+            Surface::Barren => 0.2,
+            Surface::Volcanic => 0.1,
+            Surface::Gaseous => 0.0,
+        }
+    }
+}
+
+/// The physical attributes of a body that aren't needed to render or move it
+pub struct Environment {
+    /// How dense the body is, relative to earth. Together with the body radius this determines surface gravity
+    pub density: f32,
+    pub atmosphere: Atmosphere,
+    pub surface: Surface,
+}
+
+impl Environment {
+    /// Creates a new environment
+    pub fn new(density: f32, atmosphere: Atmosphere, surface: Surface) -> Self {
+        Self {
+            density,
+            atmosphere,
+            surface,
+        }
+    }
+
+    /// The surface gravity of a body with this environment, relative to earth
+    pub fn surface_gravity(&self, body_radius: f32) -> f32 {
+        self.density * body_radius
+    }
+}
+
+/// How suitable a body is for life, used when colonizing bodies and growing their population
+pub struct Habitability {
+    /// Overall habitability, from 0 (uninhabitable) to 1 (earth-like)
+    pub score: f32,
+    /// The individual factors that were multiplied together to get the score, each from 0 to 1
+    pub factors: Vec<(&'static str, f32)>,
+}
+
+impl Habitability {
+    /// Computes the habitability of a body
+    ///
+    /// `insolation` is how much starlight reaches the body, relative to how much reaches earth
+    pub fn new(insolation: f32, body_radius: f32, environment: &Environment) -> Self {
+        let factors = vec![
+            ("temperature", log_closeness(insolation, 4.0)),
+            (
+                "gravity",
+                log_closeness(environment.surface_gravity(body_radius), 3.0),
+            ),
+            ("atmosphere", environment.atmosphere.suitability()),
+            ("surface", environment.surface.suitability()),
+        ];
+        let score = factors.iter().map(|(_name, factor)| factor).product();
+        Self { score, factors }
+    }
+}
+
+/// Computes the habitability of every body with an environment that doesn't have one yet. Called once after a
+/// system's bodies are spawned, rather than every tick, since orbits are circular and a body's distance to the stars
+/// it's lit by barely changes as it moves. Distances are measured with every body at the start of its orbit
+pub fn habitability_system(world: &mut World) {
+    let mut stars = vec![];
+    for (entity, star) in world.query::<&Star>().iter() {
+        stars.push((layout_position(world, entity), star.luminosity));
+    }
+
+    let mut habitabilities: Vec<(Entity, Habitability)> = vec![];
+    for (entity, (planet, environment)) in world
+        .query::<Without<(&Planet, &Environment), &Habitability>>()
+        .iter()
+    {
+        let position = layout_position(world, entity);
+        let star_distances: Vec<(f32, f32)> = stars
+            .iter()
+            .map(|(star_pos, luminosity)| {
+                (
+                    *luminosity,
+                    nalgebra_glm::distance(star_pos, &position) / AU,
+                )
+            })
+            .collect();
+        habitabilities.push((
+            entity,
+            Habitability::new(insolation(&star_distances), planet.body_radius, environment),
+        ));
+    }
+
+    for (entity, habitability) in habitabilities {
+        world.insert_one(entity, habitability).unwrap();
+    }
+}
+
+/// How much starlight reaches a body, relative to how much reaches earth, from the luminosity of each star and how
+/// far away it is in AU
+pub fn insolation(star_distances: &[(f32, f32)]) -> f32 {
+    star_distances
+        .iter()
+        .map(|(luminosity, distance_au)| luminosity / (distance_au * distance_au).max(f32::EPSILON))
+        .sum()
+}
+
+/// Where a body is at the start of its orbit, found by adding up the orbits of it and every body it orbits
+fn layout_position(world: &World, entity: Entity) -> nalgebra_glm::Vec3 {
+    let mut position = nalgebra_glm::vec3(0.0, 0.0, 0.0);
+    let mut current = entity;
+    while let Ok(planet) = world.get::<&Planet>(current) {
+        position += nalgebra_glm::vec3(
+            planet.orbital_phase.cos() * planet.orbital_radius,
+            planet.orbital_phase.sin() * planet.orbital_radius,
+            0.0,
+        );
+        current = planet.parent_planet_id;
+    }
+    position
+}
+
+/// How close a value is to 1 on a log scale, from 1 when equal to 0 when off by `tolerance` times or more
+fn log_closeness(value: f32, tolerance: f32) -> f32 {
+    if value <= 0.0 {
+        return 0.0;
+    }
+    (1.0 - value.ln().abs() / tolerance.ln()).max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn earth() -> Environment {
+        Environment::new(1.0, Atmosphere::Breathable, Surface::Terran)
+    }
+
+    #[test]
+    fn earth_is_fully_habitable() {
+        let habitability = Habitability::new(1.0, 1.0, &earth());
+        assert!((habitability.score - 1.0).abs() < 1e-6);
+        assert_eq!(habitability.factors.len(), 4);
+    }
+
+    #[test]
+    fn score_is_product_of_factors() {
+        let environment = Environment::new(1.2, Atmosphere::Thin, Surface::Desert);
+        let habitability = Habitability::new(2.0, 0.8, &environment);
+        let product: f32 = habitability
+            .factors
+            .iter()
+            .map(|(_name, factor)| factor)
+            .product();
+        assert!((habitability.score - product).abs() < 1e-6);
+        assert!(habitability.score > 0.0 && habitability.score < 1.0);
+    }
+
+    #[test]
+    fn gas_giants_are_uninhabitable() {
+        let environment = Environment::new(0.3, Atmosphere::Dense, Surface::Gaseous);
+        assert_eq!(Habitability::new(1.0, 1.0, &environment).score, 0.0);
+    }
+
+    #[test]
+    fn too_much_or_too_little_light_is_uninhabitable() {
+        assert_eq!(Habitability::new(4.0, 1.0, &earth()).score, 0.0);
+        assert_eq!(Habitability::new(0.25, 1.0, &earth()).score, 0.0);
+        assert_eq!(Habitability::new(0.0, 1.0, &earth()).score, 0.0);
+    }
+
+    #[test]
+    fn closeness_is_symmetric_on_a_log_scale() {
+        assert!((log_closeness(2.0, 4.0) - log_closeness(0.5, 4.0)).abs() < 1e-6);
+        assert!((log_closeness(2.0, 4.0) - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn insolation_falls_off_with_distance_squared() {
+        assert!((insolation(&[(1.0, 1.0)]) - 1.0).abs() < 1e-6);
+        assert!((insolation(&[(1.0, 2.0)]) - 0.25).abs() < 1e-6);
+        assert!((insolation(&[(4.0, 2.0)]) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn insolation_adds_up_every_star() {
+        let binary = insolation(&[(0.5, 1.0), (0.5, 1.0)]);
+        assert!((binary - 1.0).abs() < 1e-6);
+        assert_eq!(insolation(&[]), 0.0);
+    }
+}
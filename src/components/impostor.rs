@@ -0,0 +1,51 @@
+//! This module is responsible for drawing icons over bodies that are too far away to see
+
+use apricot::{
+    app::App,
+    rectangle::Rectangle,
+    render_core::{ModelComponent, TextureId},
+};
+use hecs::World;
+
+use super::{
+    planet::Planet,
+    projection::{body_spheres, ScreenProjection},
+};
+
+/// Bodies that appear smaller than this radius on the screen, in pixels, are drawn as an icon instead
+const MIN_PIXEL_RADIUS: f32 = 3.0;
+/// The size of the icon texture, in pixels
+const ICON_SIZE: f32 = 64.0;
+
+/// Draws an icon over every body that is too small to see on the screen, so that the whole system stays readable when
+/// zoomed out. Once a body appears large enough, its icon disappears and only its mesh is seen.
+pub fn impostor_system(
+    app: &App,
+    world: &World,
+    projection: &ScreenProjection,
+    icon_texture_id: TextureId,
+) {
+    let spheres = body_spheres(world);
+    for (entity, (model, planet)) in world.query::<(&ModelComponent, &Planet)>().iter() {
+        let position = model.get_position();
+        if projection.pixel_radius(position, planet.body_radius) >= MIN_PIXEL_RADIUS {
+            continue;
+        }
+        let Some(screen_pos) = projection.project(position) else {
+            continue;
+        };
+        if projection.is_occluded(entity, position, &spheres) {
+            continue;
+        }
+        app.renderer.copy_texture(
+            Rectangle::new(
+                screen_pos.x - MIN_PIXEL_RADIUS,
+                screen_pos.y - MIN_PIXEL_RADIUS,
+                MIN_PIXEL_RADIUS * 2.0,
+                MIN_PIXEL_RADIUS * 2.0,
+            ),
+            icon_texture_id,
+            Rectangle::new(0.0, 0.0, ICON_SIZE, ICON_SIZE),
+        );
+    }
+}
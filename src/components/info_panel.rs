@@ -0,0 +1,269 @@
+//! This module is responsible for defining the info panel, which shows data about the selected planetary body
+
+use std::sync::Arc;
+
+use apricot::{app::App, rectangle::Rectangle};
+use hecs::{Entity, World};
+
+use super::{
+    button::{Event, EventQueue},
+    calendar::DAYS_PER_YEAR,
+    construction::{Colony, Station},
+    habitability::Habitability,
+    planet::Planet,
+    text_metrics::TextMetrics,
+};
+
+/// A stat of a body, shown in the info panel and in the body's tooltip
+pub(crate) struct BodyStat {
+    /// What the stat is
+    pub name: String,
+    /// The stat's value, formatted
+    pub value: String,
+    /// How good the value is, from 0 to 1, for stats worth coloring by how good they are
+    pub rating: Option<f32>,
+    /// How many levels the stat is nested under the stat before it, for the parts of a stat
+    pub depth: usize,
+}
+
+impl BodyStat {
+    /// Creates a stat that isn't rated or nested
+    fn new(name: &str, value: String) -> Self {
+        Self {
+            name: name.to_string(),
+            value,
+            rating: None,
+            depth: 0,
+        }
+    }
+
+    /// The stat as plain text
+    pub fn text(&self) -> String {
+        format!("{}: {}", self.name, self.value)
+    }
+}
+
+/// A single line of text in the info panel
+struct InfoLine {
+    /// The text to display
+    text: String,
+    /// How far the line is indented, in characters
+    indent: usize,
+    /// The body to jump to when this line is clicked, if any
+    target: Option<Entity>,
+}
+
+/// Panel that shows the physical data of the selected planetary body, and lets the user jump to its parent and moons
+pub struct InfoPanel {
+    /// The top-left corner of the panel
+    position: nalgebra_glm::Vec2,
+    /// The lines of text currently in the panel, rebuilt every update
+    lines: Vec<InfoLine>,
+    /// The size of the font the panel's text is laid out with
+    metrics: TextMetrics,
+    /// The event queue that stores events triggered by this panel
+    event_queue: Arc<EventQueue>,
+}
+
+impl InfoPanel {
+    /// Creates a new, empty info panel
+    pub fn new(
+        position: nalgebra_glm::Vec2,
+        metrics: TextMetrics,
+        event_queue: Arc<EventQueue>,
+    ) -> Self {
+        Self {
+            position,
+            lines: vec![],
+            metrics,
+            event_queue,
+        }
+    }
+
+    /// Moves the panel, for when the window is resized
+    pub fn set_position(&mut self, position: nalgebra_glm::Vec2) {
+        self.position = position;
+    }
+
+    /// Rebuilds the panel for the selected body and its colony, if it has one, and checks if any of the parent or moon
+    /// lines are clicked
+    pub fn update(&mut self, app: &App, world: &World, selected: Entity, colony: Option<&Colony>) {
+        self.lines = Self::build_lines(world, selected, colony);
+
+        if !app.mouse_left_clicked {
+            return;
+        }
+        for (i, line) in self.lines.iter().enumerate() {
+            if let Some(target) = line.target {
+                if self.line_rect(i, line).contains_point(&app.mouse_pos) {
+                    self.event_queue.push(Event::BodyClicked(target));
+                }
+            }
+        }
+    }
+
+    /// Renders the info panel to the screen
+    pub fn render(&self, app: &App) {
+        for (i, line) in self.lines.iter().enumerate() {
+            let is_hovered =
+                line.target.is_some() && self.line_rect(i, line).contains_point(&app.mouse_pos);
+            let text = if is_hovered {
+                format!("> {}", line.text)
+            } else {
+                line.text.clone()
+            };
+            app.renderer
+                .draw_text(self.line_pos(i, line), text.as_str());
+        }
+    }
+
+    /// The top-left corner of the `i`th line of the panel on the screen
+    fn line_pos(&self, i: usize, line: &InfoLine) -> nalgebra_glm::Vec2 {
+        nalgebra_glm::vec2(
+            self.position.x + self.metrics.char_width * line.indent as f32,
+            self.position.y + self.metrics.line_height * i as f32,
+        )
+    }
+
+    /// The rectangle that the `i`th line of the panel covers on the screen
+    fn line_rect(&self, i: usize, line: &InfoLine) -> Rectangle {
+        let pos = self.line_pos(i, line);
+        Rectangle::new(
+            pos.x,
+            pos.y,
+            self.metrics.char_width * line.text.len() as f32,
+            self.metrics.line_height,
+        )
+    }
+
+    /// Builds the lines of text that describe a body
+    fn build_lines(world: &World, selected: Entity, colony: Option<&Colony>) -> Vec<InfoLine> {
+        let mut lines = vec![];
+        let Ok(planet) = world.get::<&Planet>(selected) else {
+            return lines;
+        };
+
+        lines.push(InfoLine::new(planet.name.clone(), 0, None));
+        for stat in body_stats(world, selected, colony) {
+            lines.push(InfoLine::new(stat.text(), stat.depth + 1, None));
+        }
+
+        match world.get::<&Planet>(planet.parent_planet_id) {
+            Ok(parent) => lines.push(InfoLine::new(
+                format!("parent: {}", parent.name),
+                1,
+                Some(planet.parent_planet_id),
+            )),
+            Err(_) if planet.orbital_radius > 0.0 => {
+                lines.push(InfoLine::new("parent: barycenter".to_string(), 1, None))
+            }
+            Err(_) => {}
+        }
+
+        let mut children: Vec<(Entity, String, bool)> = world
+            .query::<(&Planet, Option<&Station>)>()
+            .iter()
+            .filter(|(_entity, (child, _station))| child.parent_planet_id == selected)
+            .map(|(entity, (child, station))| (entity, child.name.clone(), station.is_some()))
+            .collect();
+        children.sort_by(|a, b| a.1.cmp(&b.1));
+        // Stations orbit like moons, but are listed apart from them
+        for (heading, stations) in [("moons:", false), ("stations:", true)] {
+            if !children.iter().any(|child| child.2 == stations) {
+                continue;
+            }
+            lines.push(InfoLine::new(heading.to_string(), 1, None));
+            for (entity, name, _is_station) in children.iter().filter(|child| child.2 == stations) {
+                lines.push(InfoLine::new(name.clone(), 2, Some(*entity)));
+            }
+        }
+
+        lines
+    }
+}
+
+impl InfoLine {
+    /// Creates a new line of text
+    fn new(text: String, indent: usize, target: Option<Entity>) -> Self {
+        Self {
+            text,
+            indent,
+            target,
+        }
+    }
+}
+
+/// The stats of a body, in the order they're shown. Settled bodies also show their colony's stats
+pub(crate) fn body_stats(world: &World, entity: Entity, colony: Option<&Colony>) -> Vec<BodyStat> {
+    let mut stats = vec![];
+    let Ok(planet) = world.get::<&Planet>(entity) else {
+        return stats;
+    };
+
+    stats.push(BodyStat::new(
+        "radius",
+        format!("{:.2}", planet.body_radius),
+    ));
+    if planet.orbital_radius > 0.0 {
+        stats.push(BodyStat::new(
+            "orbital radius",
+            format!("{:.0}", planet.orbital_radius),
+        ));
+        stats.push(BodyStat::new(
+            "orbital period",
+            format!("{:.2} years", planet.orbital_time_years),
+        ));
+    }
+    if planet.day_time_years != 0.0 {
+        stats.push(BodyStat::new(
+            "day length",
+            format!("{:.1} days", planet.day_time_years * DAYS_PER_YEAR),
+        ));
+    }
+
+    if let Ok(station) = world.get::<&Station>(entity) {
+        stats.push(BodyStat::new("shipyard", "yes".to_string()));
+        stats.push(BodyStat::new(
+            "sensors",
+            format!("{} jumps", station.sensor_range),
+        ));
+        stats.push(BodyStat::new("defense", format!("{}", station.defense)));
+    }
+
+    if let Some(colony) = colony {
+        stats.push(BodyStat::new("owner", "your empire".to_string()));
+        stats.push(BodyStat::new("colony", colony.name.clone()));
+        stats.push(BodyStat {
+            rating: Some(colony.population / colony.population_capacity().max(1.0)),
+            ..BodyStat::new(
+                "population",
+                format!(
+                    "{:.1} of {:.0} million",
+                    colony.population,
+                    colony.population_capacity()
+                ),
+            )
+        });
+        stats.push(BodyStat::new(
+            "income",
+            format!("{} credits a turn", colony.income()),
+        ));
+    }
+
+    if let Ok(habitability) = world.get::<&Habitability>(entity) {
+        stats.push(BodyStat {
+            rating: Some(habitability.score),
+            ..BodyStat::new(
+                "habitability",
+                format!("{:.0}%", habitability.score * 100.0),
+            )
+        });
+        for (name, factor) in habitability.factors.iter() {
+            stats.push(BodyStat {
+                depth: 1,
+                ..BodyStat::new(name, format!("{:.0}%", factor * 100.0))
+            });
+        }
+    }
+    stats
+}
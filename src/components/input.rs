@@ -0,0 +1,478 @@
+//! This module is responsible for mapping keys and mouse buttons to game actions, so that controls can be rebound and
+//! saved to a config file. Each action's state is sampled once a tick, so presses and releases are edge-triggered.
+
+use std::fs;
+
+use apricot::app::App;
+use sdl2::keyboard::Scancode;
+
+/// Where the controls are saved to
+pub const CONTROLS_PATH: &str = "controls.txt";
+/// How many actions there are
+const ACTION_COUNT: usize = 43;
+/// How much holding a zoom key zooms each tick, compared to one notch of the mouse wheel
+const ZOOM_KEY_SPEED: f32 = 0.25;
+
+/// Something the user can do, that can be bound to a key or mouse button
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Select the next body in the system
+    SelectNext,
+    /// Held while moving the mouse to swivel the camera
+    Rotate,
+    /// Held to zoom the camera in, on top of the mouse wheel
+    ZoomIn,
+    /// Held to zoom the camera out, on top of the mouse wheel
+    ZoomOut,
+    /// Start the next turn, like the next-turn button
+    EndTurn,
+    /// Switch between the system view and the galaxy map
+    GalaxyMap,
+    /// Write the game to the save file
+    QuickSave,
+    /// Held to fly forward in free-fly mode
+    FlyForward,
+    /// Held to fly backward in free-fly mode
+    FlyBack,
+    /// Held to fly left in free-fly mode
+    FlyLeft,
+    /// Held to fly right in free-fly mode
+    FlyRight,
+    /// Open and close the controls screen
+    Controls,
+    /// Open and close the options screen
+    Options,
+    /// Show more or less of the notification log
+    EventLog,
+    /// Open and close the latest turn report
+    TurnReport,
+    /// Open and close the construction panel of the selected colony
+    Construction,
+    /// Go back to turn-based mode
+    TurnBased,
+    /// Pause and unpause real-time mode
+    Pause,
+    /// Play in real-time at normal speed
+    SpeedNormal,
+    /// Play in real-time at double speed
+    SpeedDouble,
+    /// Play in real-time at five times speed
+    SpeedQuintuple,
+    /// Switch to the orbit camera
+    CameraOrbit,
+    /// Switch to the free-fly camera
+    CameraFreeFly,
+    /// Switch to the track camera
+    CameraTrack,
+    /// Switch to the top-down camera
+    CameraTopDown,
+    /// Held while pressing a bookmark to save the camera to it
+    SaveBookmark,
+    /// Held while pressing a bookmark to jump the camera to it
+    RecallBookmark,
+    Bookmark0,
+    Bookmark1,
+    Bookmark2,
+    Bookmark3,
+    Bookmark4,
+    Bookmark5,
+    Bookmark6,
+    Bookmark7,
+    Bookmark8,
+    Bookmark9,
+    /// Go back to the body looked at before
+    HistoryBack,
+    /// Go forward to the body looked at after
+    HistoryForward,
+    /// Select the row above in the outliner
+    OutlinerUp,
+    /// Select the row below in the outliner
+    OutlinerDown,
+    /// Collapse the selected row in the outliner, or select its parent
+    OutlinerCollapse,
+    /// Expand the selected row in the outliner, or select its first child
+    OutlinerExpand,
+}
+
+impl Action {
+    /// Every action, in the order they're shown on the controls screen
+    pub const ALL: [Action; ACTION_COUNT] = [
+        Action::SelectNext,
+        Action::Rotate,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::EndTurn,
+        Action::GalaxyMap,
+        Action::QuickSave,
+        Action::FlyForward,
+        Action::FlyBack,
+        Action::FlyLeft,
+        Action::FlyRight,
+        Action::Controls,
+        Action::Options,
+        Action::EventLog,
+        Action::TurnReport,
+        Action::Construction,
+        Action::TurnBased,
+        Action::Pause,
+        Action::SpeedNormal,
+        Action::SpeedDouble,
+        Action::SpeedQuintuple,
+        Action::CameraOrbit,
+        Action::CameraFreeFly,
+        Action::CameraTrack,
+        Action::CameraTopDown,
+        Action::SaveBookmark,
+        Action::RecallBookmark,
+        Action::Bookmark0,
+        Action::Bookmark1,
+        Action::Bookmark2,
+        Action::Bookmark3,
+        Action::Bookmark4,
+        Action::Bookmark5,
+        Action::Bookmark6,
+        Action::Bookmark7,
+        Action::Bookmark8,
+        Action::Bookmark9,
+        Action::HistoryBack,
+        Action::HistoryForward,
+        Action::OutlinerUp,
+        Action::OutlinerDown,
+        Action::OutlinerCollapse,
+        Action::OutlinerExpand,
+    ];
+
+    /// The bookmark actions, in the order of the bookmarks they're for
+    pub const BOOKMARKS: [Action; 10] = [
+        Action::Bookmark0,
+        Action::Bookmark1,
+        Action::Bookmark2,
+        Action::Bookmark3,
+        Action::Bookmark4,
+        Action::Bookmark5,
+        Action::Bookmark6,
+        Action::Bookmark7,
+        Action::Bookmark8,
+        Action::Bookmark9,
+    ];
+
+    /// The name of the action in the controls file
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::SelectNext => "select_next",
+            Action::Rotate => "rotate",
+            Action::ZoomIn => "zoom_in",
+            Action::ZoomOut => "zoom_out",
+            Action::EndTurn => "end_turn",
+            Action::GalaxyMap => "galaxy_map",
+            Action::QuickSave => "quick_save",
+            Action::FlyForward => "fly_forward",
+            Action::FlyBack => "fly_back",
+            Action::FlyLeft => "fly_left",
+            Action::FlyRight => "fly_right",
+            Action::Controls => "controls",
+            Action::Options => "options",
+            Action::EventLog => "event_log",
+            Action::TurnReport => "turn_report",
+            Action::Construction => "construction",
+            Action::TurnBased => "turn_based",
+            Action::Pause => "pause",
+            Action::SpeedNormal => "speed_normal",
+            Action::SpeedDouble => "speed_double",
+            Action::SpeedQuintuple => "speed_quintuple",
+            Action::CameraOrbit => "camera_orbit",
+            Action::CameraFreeFly => "camera_free_fly",
+            Action::CameraTrack => "camera_track",
+            Action::CameraTopDown => "camera_top_down",
+            Action::SaveBookmark => "save_bookmark",
+            Action::RecallBookmark => "recall_bookmark",
+            Action::Bookmark0 => "bookmark_0",
+            Action::Bookmark1 => "bookmark_1",
+            Action::Bookmark2 => "bookmark_2",
+            Action::Bookmark3 => "bookmark_3",
+            Action::Bookmark4 => "bookmark_4",
+            Action::Bookmark5 => "bookmark_5",
+            Action::Bookmark6 => "bookmark_6",
+            Action::Bookmark7 => "bookmark_7",
+            Action::Bookmark8 => "bookmark_8",
+            Action::Bookmark9 => "bookmark_9",
+            Action::HistoryBack => "history_back",
+            Action::HistoryForward => "history_forward",
+            Action::OutlinerUp => "outliner_up",
+            Action::OutlinerDown => "outliner_down",
+            Action::OutlinerCollapse => "outliner_collapse",
+            Action::OutlinerExpand => "outliner_expand",
+        }
+    }
+
+    /// Text to show on the controls screen for this action
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::SelectNext => "select next body",
+            Action::Rotate => "rotate camera",
+            Action::ZoomIn => "zoom in",
+            Action::ZoomOut => "zoom out",
+            Action::EndTurn => "end turn",
+            Action::GalaxyMap => "galaxy map",
+            Action::QuickSave => "quick save",
+            Action::FlyForward => "fly forward",
+            Action::FlyBack => "fly back",
+            Action::FlyLeft => "fly left",
+            Action::FlyRight => "fly right",
+            Action::Controls => "controls",
+            Action::Options => "options",
+            Action::EventLog => "event log",
+            Action::TurnReport => "turn report",
+            Action::Construction => "construction",
+            Action::TurnBased => "turn-based",
+            Action::Pause => "pause",
+            Action::SpeedNormal => "speed 1x",
+            Action::SpeedDouble => "speed 2x",
+            Action::SpeedQuintuple => "speed 5x",
+            Action::CameraOrbit => "orbit camera",
+            Action::CameraFreeFly => "free-fly camera",
+            Action::CameraTrack => "track camera",
+            Action::CameraTopDown => "top-down camera",
+            Action::SaveBookmark => "save bookmark (hold)",
+            Action::RecallBookmark => "recall bookmark (hold)",
+            Action::Bookmark0 => "bookmark 0",
+            Action::Bookmark1 => "bookmark 1",
+            Action::Bookmark2 => "bookmark 2",
+            Action::Bookmark3 => "bookmark 3",
+            Action::Bookmark4 => "bookmark 4",
+            Action::Bookmark5 => "bookmark 5",
+            Action::Bookmark6 => "bookmark 6",
+            Action::Bookmark7 => "bookmark 7",
+            Action::Bookmark8 => "bookmark 8",
+            Action::Bookmark9 => "bookmark 9",
+            Action::HistoryBack => "history back",
+            Action::HistoryForward => "history forward",
+            Action::OutlinerUp => "outliner up",
+            Action::OutlinerDown => "outliner down",
+            Action::OutlinerCollapse => "outliner collapse",
+            Action::OutlinerExpand => "outliner expand",
+        }
+    }
+
+    /// What the action is bound to when there's no controls file
+    fn default_binding(&self) -> Binding {
+        match self {
+            Action::SelectNext => Binding::Key(Scancode::Return),
+            Action::Rotate => Binding::MouseLeft,
+            Action::ZoomIn => Binding::Key(Scancode::Equals),
+            Action::ZoomOut => Binding::Key(Scancode::Minus),
+            Action::EndTurn => Binding::Key(Scancode::End),
+            Action::GalaxyMap => Binding::Key(Scancode::G),
+            Action::QuickSave => Binding::Key(Scancode::F5),
+            Action::FlyForward => Binding::Key(Scancode::W),
+            Action::FlyBack => Binding::Key(Scancode::S),
+            Action::FlyLeft => Binding::Key(Scancode::A),
+            Action::FlyRight => Binding::Key(Scancode::D),
+            Action::Controls => Binding::Key(Scancode::F10),
+            Action::Options => Binding::Key(Scancode::F9),
+            Action::EventLog => Binding::Key(Scancode::L),
+            Action::TurnReport => Binding::Key(Scancode::R),
+            Action::Construction => Binding::Key(Scancode::B),
+            Action::TurnBased => Binding::Key(Scancode::Grave),
+            Action::Pause => Binding::Key(Scancode::Space),
+            Action::SpeedNormal => Binding::Key(Scancode::F6),
+            Action::SpeedDouble => Binding::Key(Scancode::F7),
+            Action::SpeedQuintuple => Binding::Key(Scancode::F8),
+            Action::CameraOrbit => Binding::Key(Scancode::F1),
+            Action::CameraFreeFly => Binding::Key(Scancode::F2),
+            Action::CameraTrack => Binding::Key(Scancode::F3),
+            Action::CameraTopDown => Binding::Key(Scancode::F4),
+            Action::SaveBookmark => Binding::Key(Scancode::LCtrl),
+            Action::RecallBookmark => Binding::Key(Scancode::LAlt),
+            Action::Bookmark0 => Binding::Key(Scancode::Num0),
+            Action::Bookmark1 => Binding::Key(Scancode::Num1),
+            Action::Bookmark2 => Binding::Key(Scancode::Num2),
+            Action::Bookmark3 => Binding::Key(Scancode::Num3),
+            Action::Bookmark4 => Binding::Key(Scancode::Num4),
+            Action::Bookmark5 => Binding::Key(Scancode::Num5),
+            Action::Bookmark6 => Binding::Key(Scancode::Num6),
+            Action::Bookmark7 => Binding::Key(Scancode::Num7),
+            Action::Bookmark8 => Binding::Key(Scancode::Num8),
+            Action::Bookmark9 => Binding::Key(Scancode::Num9),
+            Action::HistoryBack => Binding::Key(Scancode::LeftBracket),
+            Action::HistoryForward => Binding::Key(Scancode::RightBracket),
+            Action::OutlinerUp => Binding::Key(Scancode::Up),
+            Action::OutlinerDown => Binding::Key(Scancode::Down),
+            Action::OutlinerCollapse => Binding::Key(Scancode::Left),
+            Action::OutlinerExpand => Binding::Key(Scancode::Right),
+        }
+    }
+
+    /// Whether the action can be bound to the left mouse button. Clicking is also how the UI is used, so only
+    /// actions that are held while dragging the mouse can share it
+    fn allows_mouse(&self) -> bool {
+        *self == Action::Rotate
+    }
+}
+
+/// A key or mouse button an action can be bound to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Binding {
+    Key(Scancode),
+    MouseLeft,
+}
+
+impl Binding {
+    /// The name of the binding in the controls file, and on the controls screen
+    pub fn name(&self) -> String {
+        match self {
+            Binding::Key(scancode) => scancode.name().to_string(),
+            Binding::MouseLeft => "Mouse Left".to_string(),
+        }
+    }
+
+    /// Parses the name of a binding
+    fn from_name(name: &str) -> Option<Self> {
+        if name == "Mouse Left" {
+            Some(Binding::MouseLeft)
+        } else {
+            Scancode::from_name(name).map(Binding::Key)
+        }
+    }
+
+    /// Whether the key or mouse button is held down
+    fn is_down(&self, app: &App) -> bool {
+        match self {
+            Binding::Key(scancode) => app.keys[*scancode as usize],
+            Binding::MouseLeft => app.mouse_left_down,
+        }
+    }
+}
+
+/// What each action is bound to, and whether each action is held down
+pub struct InputMap {
+    /// The binding for each action, indexed by the action
+    bindings: [Binding; ACTION_COUNT],
+    /// Whether each action is held down this tick
+    curr_states: [bool; ACTION_COUNT],
+    /// Whether each action was held down last tick, used for edge-triggering
+    prev_states: [bool; ACTION_COUNT],
+    /// Whether each key was held down last tick, for picking up any key at all, like when rebinding
+    prev_keys: Vec<bool>,
+    /// The first key that started being held down this tick, if any
+    pressed_key: Option<Scancode>,
+}
+
+impl InputMap {
+    /// Creates a new input map, with every action bound to its default
+    pub fn new() -> Self {
+        Self {
+            bindings: Action::ALL.map(|action| action.default_binding()),
+            curr_states: [false; ACTION_COUNT],
+            prev_states: [false; ACTION_COUNT],
+            prev_keys: vec![],
+            pressed_key: None,
+        }
+    }
+
+    /// Reads the controls file. Actions missing from the file, or with bindings that can't be parsed, keep their
+    /// default bindings
+    pub fn load(path: &str) -> Self {
+        let mut input_map = Self::new();
+        let Ok(text) = fs::read_to_string(path) else {
+            return input_map;
+        };
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            let parsed = line.split_once('=').and_then(|(name, binding)| {
+                let action = Action::ALL
+                    .into_iter()
+                    .find(|action| action.name() == name.trim())?;
+                Some((action, Binding::from_name(binding.trim())?))
+            });
+            let bound = parsed.and_then(|(action, binding)| input_map.bind(action, binding).ok());
+            if bound.is_none() {
+                println!("ignoring bad line in {}: {:?}", path, line);
+            }
+        }
+        input_map
+    }
+
+    /// Writes the controls file
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        let text: String = Action::ALL
+            .iter()
+            .map(|action| format!("{} = {}\n", action.name(), self.binding(*action).name()))
+            .collect();
+        fs::write(path, text)
+    }
+
+    /// Samples whether each action is held down. Should be called once a tick, before any of the other queries
+    pub fn update(&mut self, app: &App) {
+        self.prev_states = self.curr_states;
+        for (state, binding) in self.curr_states.iter_mut().zip(self.bindings.iter()) {
+            *state = binding.is_down(app);
+        }
+        self.pressed_key = app.keys.iter().enumerate().find_map(|(i, curr_state)| {
+            let prev_state = self.prev_keys.get(i).copied().unwrap_or(false);
+            if *curr_state && !prev_state {
+                Scancode::from_i32(i as i32)
+            } else {
+                None
+            }
+        });
+        self.prev_keys = app.keys.to_vec();
+    }
+
+    /// What an action is bound to
+    pub fn binding(&self, action: Action) -> Binding {
+        self.bindings[action as usize]
+    }
+
+    /// Rebinds an action. If another action already has the binding, the two actions swap bindings, so that no key
+    /// does two things at once. Returns why the binding isn't allowed, if it isn't
+    pub fn bind(&mut self, action: Action, binding: Binding) -> Result<(), String> {
+        if binding == Binding::MouseLeft && !action.allows_mouse() {
+            return Err(format!(
+                "{} can't be bound to {}, it's for clicking",
+                action.label(),
+                binding.name()
+            ));
+        }
+        let old = self.bindings[action as usize];
+        if let Some(other) = self.bindings.iter().position(|other| *other == binding) {
+            if old == Binding::MouseLeft && !Action::ALL[other].allows_mouse() {
+                return Err(format!(
+                    "{} is used by {}, which can't take {}",
+                    binding.name(),
+                    Action::ALL[other].label(),
+                    old.name()
+                ));
+            }
+            self.bindings[other] = old;
+        }
+        self.bindings[action as usize] = binding;
+        Ok(())
+    }
+
+    /// The first key that started being held down this tick, whether or not it's bound to anything
+    pub fn pressed_key(&self) -> Option<Scancode> {
+        self.pressed_key
+    }
+
+    /// Whether an action is held down
+    pub fn is_down(&self, action: Action) -> bool {
+        self.curr_states[action as usize]
+    }
+
+    /// Whether an action started being held down this tick
+    pub fn pressed(&self, action: Action) -> bool {
+        self.curr_states[action as usize] && !self.prev_states[action as usize]
+    }
+
+    /// How much to zoom in this tick, from the mouse wheel and the zoom keys. Negative zooms out
+    pub fn zoom(&self, app: &App) -> f32 {
+        let mut zoom = app.mouse_wheel as f32;
+        if self.is_down(Action::ZoomIn) {
+            zoom += ZOOM_KEY_SPEED;
+        }
+        if self.is_down(Action::ZoomOut) {
+            zoom -= ZOOM_KEY_SPEED;
+        }
+        zoom
+    }
+}
@@ -0,0 +1,130 @@
+//! This module is responsible for drawing the names of planetary bodies next to where they appear on screen
+
+use apricot::{app::App, render_core::ModelComponent};
+use hecs::{Entity, World};
+
+use super::{
+    planet::Planet,
+    projection::{body_spheres, ScreenProjection},
+    star::uniform_location,
+    text_metrics::TextMetrics,
+};
+
+/// How far to the right of a body its label is drawn, in pixels at a UI scale of 1
+const LABEL_OFFSET: f32 = 8.0;
+/// How many times its orbital radius away the camera can get from a body before its label starts to fade
+const FADE_ORBITS: f32 = 20.0;
+/// How many times its body radius away the camera can get from a body before its label starts to fade
+const FADE_RADII: f32 = 1000.0;
+
+/// A label that survived culling, waiting to be placed on the screen
+struct Label {
+    /// The body the label is for
+    entity: Entity,
+    /// The text of the label
+    name: String,
+    /// Where the body appears on the screen, in pixels
+    screen_pos: nalgebra_glm::Vec2,
+    /// How visible the label is, from 0 to 1
+    opacity: f32,
+    /// The radius of the body, bigger bodies win when labels overlap
+    body_radius: f32,
+}
+
+/// Draws the name of every visible body next to it on the screen.
+///
+/// Labels are hidden when their body is behind the camera or behind another body. When labels overlap, the selected
+/// body's label wins, then the most opaque, then the biggest body's. Labels fade out as the camera moves away from
+/// their body, so that moons don't clutter the view when looking at the whole system.
+pub fn label_system(
+    app: &App,
+    world: &World,
+    projection: &ScreenProjection,
+    selected: Entity,
+    metrics: &TextMetrics,
+    program_2d_id: u32,
+) {
+    let spheres = body_spheres(world);
+
+    let mut labels = vec![];
+    for (entity, (model, planet)) in world.query::<(&ModelComponent, &Planet)>().iter() {
+        let position = model.get_position();
+        let Some(screen_pos) = projection.project(position) else {
+            continue;
+        };
+        if projection.is_occluded(entity, position, &spheres) {
+            continue;
+        }
+
+        let distance = nalgebra_glm::distance(&projection.camera_pos, &position);
+        let opacity = if entity == selected || planet.orbital_radius == 0.0 {
+            1.0
+        } else {
+            let fade_distance =
+                (planet.orbital_radius * FADE_ORBITS).max(planet.body_radius * FADE_RADII);
+            (2.0 - distance / fade_distance).clamp(0.0, 1.0)
+        };
+        if opacity <= 0.0 {
+            continue;
+        }
+
+        labels.push(Label {
+            entity,
+            name: planet.name.clone(),
+            screen_pos,
+            opacity,
+            body_radius: planet.body_radius,
+        });
+    }
+
+    labels.sort_by(|a, b| {
+        (b.entity == selected)
+            .cmp(&(a.entity == selected))
+            .then(b.opacity.total_cmp(&a.opacity))
+            .then(b.body_radius.total_cmp(&a.body_radius))
+    });
+
+    // Greedily place labels, skipping any that would overlap one already placed
+    let mut placed: Vec<(nalgebra_glm::Vec2, nalgebra_glm::Vec2)> = vec![];
+    for label in labels {
+        let min = label.screen_pos
+            + nalgebra_glm::vec2(metrics.scale(LABEL_OFFSET), -metrics.line_height * 0.5);
+        let max = min
+            + nalgebra_glm::vec2(
+                metrics.char_width * label.name.len() as f32,
+                metrics.line_height,
+            );
+        let overlaps = placed.iter().any(|(other_min, other_max)| {
+            min.x < other_max.x && max.x > other_min.x && min.y < other_max.y && max.y > other_min.y
+        });
+        if overlaps {
+            continue;
+        }
+        placed.push((min, max));
+
+        set_opacity(program_2d_id, label.opacity);
+        app.renderer.draw_text(min, &label.name);
+    }
+    set_opacity(program_2d_id, 1.0);
+}
+
+/// Sets how opaque everything drawn with the 2d shader is, from 0 to 1
+pub fn set_opacity(program_2d_id: u32, opacity: f32) {
+    unsafe {
+        gl::UseProgram(program_2d_id);
+        gl::Uniform1f(uniform_location(program_2d_id, c"u_opacity"), opacity);
+    }
+}
+
+/// Sets the color everything drawn with the 2d shader is multiplied by. White leaves textures and text unchanged
+pub fn set_color(program_2d_id: u32, color: nalgebra_glm::Vec3) {
+    unsafe {
+        gl::UseProgram(program_2d_id);
+        gl::Uniform3f(
+            uniform_location(program_2d_id, c"u_color"),
+            color.x,
+            color.y,
+            color.z,
+        );
+    }
+}
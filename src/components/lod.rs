@@ -0,0 +1,233 @@
+//! This module is responsible for mesh level-of-detail. Body meshes are procedurally generated icospheres at several
+//! subdivisions, and each body swaps between them based on how big it appears on the screen.
+
+use std::{collections::HashMap, f32::consts::PI, fmt::Write};
+
+use apricot::render_core::{MeshId, ModelComponent, RenderContext, TextureId};
+use hecs::{Entity, World};
+
+use super::{planet::Planet, projection::ScreenProjection};
+
+/// The subdivision levels that icospheres are generated at, from least to most detailed
+const SUBDIVISIONS: [u32; 5] = [0, 1, 2, 3, 4];
+/// The on-screen radius, in pixels, a body must appear at before each subdivision level is used
+const MIN_PIXEL_RADII: [f32; 5] = [0.0, 8.0, 32.0, 128.0, 384.0];
+
+/// The detail levels a body's mesh can be drawn at
+pub struct MeshLod {
+    /// Each level's mesh, paired with the on-screen radius in pixels the body must appear at to use it
+    levels: Vec<(MeshId, f32)>,
+    /// The texture to draw every level with
+    texture_id: TextureId,
+    /// Index into `levels` of the mesh the body is currently drawn with
+    current: usize,
+}
+
+impl MeshLod {
+    /// Creates the detail levels for a body. Smooth levels have per-vertex normals, for gaseous bodies, otherwise
+    /// each face is flat shaded
+    pub fn new(renderer: &RenderContext, smooth: bool, texture_id: TextureId) -> Self {
+        let levels = SUBDIVISIONS
+            .iter()
+            .zip(MIN_PIXEL_RADII)
+            .map(|(subdivisions, min_pixel_radius)| {
+                (
+                    renderer
+                        .get_mesh_id_from_name(&icosphere_name(*subdivisions, smooth))
+                        .unwrap(),
+                    min_pixel_radius,
+                )
+            })
+            .collect();
+        Self {
+            levels,
+            texture_id,
+            current: 0,
+        }
+    }
+
+    /// The mesh a body is drawn with before its size on the screen is known
+    pub fn initial_mesh(&self) -> MeshId {
+        self.levels[self.current].0
+    }
+
+    /// The most detailed level whose minimum on-screen radius the body meets
+    fn level_for(&self, pixel_radius: f32) -> usize {
+        self.levels
+            .iter()
+            .rposition(|(_mesh_id, min_pixel_radius)| pixel_radius >= *min_pixel_radius)
+            .unwrap_or(0)
+    }
+}
+
+/// Generates and adds icosphere meshes at every subdivision level to the renderer, both flat and smooth shaded
+pub fn add_icosphere_meshes(renderer: &RenderContext) {
+    for subdivisions in SUBDIVISIONS {
+        for smooth in [false, true] {
+            renderer.add_mesh_from_obj(
+                icosphere_obj(subdivisions, smooth).as_bytes(),
+                Some(&icosphere_name(subdivisions, smooth)),
+            );
+        }
+    }
+}
+
+/// Swaps each body's mesh for the detail level that suits how big it appears on the screen
+pub fn lod_system(world: &mut World, projection: &ScreenProjection) {
+    let mut swaps: Vec<(Entity, ModelComponent)> = vec![];
+    for (entity, (model, planet, lod)) in
+        world.query_mut::<(&ModelComponent, &Planet, &mut MeshLod)>()
+    {
+        let position = model.get_position();
+        let level = lod.level_for(projection.pixel_radius(position, planet.body_radius));
+        if level == lod.current {
+            continue;
+        }
+        lod.current = level;
+        swaps.push((
+            entity,
+            ModelComponent::new(
+                lod.levels[level].0,
+                lod.texture_id,
+                position,
+                nalgebra_glm::vec3(planet.body_radius, planet.body_radius, planet.body_radius),
+            ),
+        ));
+    }
+
+    for (entity, model) in swaps {
+        world.insert_one(entity, model).unwrap();
+    }
+}
+
+/// The name an icosphere mesh is registered under in the renderer
+fn icosphere_name(subdivisions: u32, smooth: bool) -> String {
+    if smooth {
+        format!("ico-smooth-{}", subdivisions)
+    } else {
+        format!("ico-{}", subdivisions)
+    }
+}
+
+/// Generates the .obj file data for a unit icosphere, with the z axis through the poles
+fn icosphere_obj(subdivisions: u32, smooth: bool) -> String {
+    let (vertices, faces) = icosphere(subdivisions);
+    let mut obj = String::new();
+
+    for vertex in vertices.iter() {
+        writeln!(obj, "v {} {} {}", vertex.x, vertex.y, vertex.z).unwrap();
+    }
+    if smooth {
+        for vertex in vertices.iter() {
+            writeln!(obj, "vn {} {} {}", vertex.x, vertex.y, vertex.z).unwrap();
+        }
+    } else {
+        for face in faces.iter() {
+            let normal = (vertices[face[0]] + vertices[face[1]] + vertices[face[2]]).normalize();
+            writeln!(obj, "vn {} {} {}", normal.x, normal.y, normal.z).unwrap();
+        }
+    }
+
+    // Texture coordinates are given per face corner, so that faces crossing the texture's seam can be unwrapped
+    for face in faces.iter() {
+        let mut uvs = face.map(|i| sphere_uv(&vertices[i]));
+        let min_v = uvs.iter().map(|uv| uv.y).fold(f32::MAX, f32::min);
+        let max_v = uvs.iter().map(|uv| uv.y).fold(f32::MIN, f32::max);
+        if max_v - min_v > 0.5 {
+            for uv in uvs.iter_mut() {
+                if uv.y < 0.5 {
+                    uv.y += 1.0;
+                }
+            }
+        }
+        for uv in uvs {
+            writeln!(obj, "vt {} {}", uv.x, uv.y).unwrap();
+        }
+    }
+
+    for (i, face) in faces.iter().enumerate() {
+        write!(obj, "f").unwrap();
+        for (corner, vertex) in face.iter().enumerate() {
+            let normal = if smooth { *vertex } else { i };
+            write!(obj, " {}/{}/{}", vertex + 1, i * 3 + corner + 1, normal + 1).unwrap();
+        }
+        writeln!(obj).unwrap();
+    }
+
+    obj
+}
+
+/// Builds the vertices and triangles of a unit icosphere, by repeatedly splitting each face of an icosahedron into
+/// four and pushing the new vertices out onto the sphere
+fn icosphere(subdivisions: u32) -> (Vec<nalgebra_glm::Vec3>, Vec<[usize; 3]>) {
+    let t = (1.0 + 5.0_f32.sqrt()) / 2.0;
+    let mut vertices: Vec<nalgebra_glm::Vec3> = [
+        (-1.0, t, 0.0),
+        (1.0, t, 0.0),
+        (-1.0, -t, 0.0),
+        (1.0, -t, 0.0),
+        (0.0, -1.0, t),
+        (0.0, 1.0, t),
+        (0.0, -1.0, -t),
+        (0.0, 1.0, -t),
+        (t, 0.0, -1.0),
+        (t, 0.0, 1.0),
+        (-t, 0.0, -1.0),
+        (-t, 0.0, 1.0),
+    ]
+    .iter()
+    .map(|(x, y, z)| nalgebra_glm::vec3(*x, *y, *z).normalize())
+    .collect();
+    let mut faces = vec![
+        [0, 11, 5],
+        [0, 5, 1],
+        [0, 1, 7],
+        [0, 7, 10],
+        [0, 10, 11],
+        [1, 5, 9],
+        [5, 11, 4],
+        [11, 10, 2],
+        [10, 7, 6],
+        [7, 1, 8],
+        [3, 9, 4],
+        [3, 4, 2],
+        [3, 2, 6],
+        [3, 6, 8],
+        [3, 8, 9],
+        [4, 9, 5],
+        [2, 4, 11],
+        [6, 2, 10],
+        [8, 6, 7],
+        [9, 8, 1],
+    ];
+
+    for _ in 0..subdivisions {
+        let mut midpoints: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut midpoint = |a: usize, b: usize, vertices: &mut Vec<nalgebra_glm::Vec3>| {
+            *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                vertices.push(((vertices[a] + vertices[b]) * 0.5).normalize());
+                vertices.len() - 1
+            })
+        };
+        faces = faces
+            .iter()
+            .flat_map(|[a, b, c]| {
+                let ab = midpoint(*a, *b, &mut vertices);
+                let bc = midpoint(*b, *c, &mut vertices);
+                let ca = midpoint(*c, *a, &mut vertices);
+                [[*a, ab, ca], [*b, bc, ab], [*c, ca, bc], [ab, bc, ca]]
+            })
+            .collect();
+    }
+
+    (vertices, faces)
+}
+
+/// Maps a point on the unit sphere to texture coordinates. Matches the layout of the body textures, where u runs from
+/// the south pole to the north pole, and v runs around the equator
+fn sphere_uv(point: &nalgebra_glm::Vec3) -> nalgebra_glm::Vec2 {
+    nalgebra_glm::vec2(
+        0.5 + point.z.clamp(-1.0, 1.0).asin() / PI,
+        (point.y.atan2(point.x) + PI) / (2.0 * PI),
+    )
+}
@@ -1,2 +1,31 @@
+pub(crate) mod anchor;
 pub(crate) mod button;
+pub(crate) mod calendar;
+pub(crate) mod camera_bookmarks;
+pub(crate) mod camera_mode;
+pub(crate) mod construction;
+pub(crate) mod construction_panel;
+pub(crate) mod controls_screen;
+pub(crate) mod galaxy;
+pub(crate) mod galaxy_map;
+pub(crate) mod habitability;
+pub(crate) mod impostor;
+pub(crate) mod info_panel;
+pub(crate) mod input;
+pub(crate) mod label;
+pub(crate) mod lod;
+pub(crate) mod notifications;
+pub(crate) mod options_screen;
+pub(crate) mod outliner;
+pub(crate) mod pathfinding;
 pub(crate) mod planet;
+pub(crate) mod projection;
+pub(crate) mod rich_text;
+pub(crate) mod save;
+pub(crate) mod settings;
+pub(crate) mod star;
+pub(crate) mod text_metrics;
+pub(crate) mod time_control;
+pub(crate) mod tooltip;
+pub(crate) mod turn_report;
+pub(crate) mod tween;
@@ -0,0 +1,316 @@
+//! This module is responsible for notifications, which tell the player what happened during a turn. Subsystems post
+//! typed notifications to the feed, which keeps them as a log of every turn, and the HUD shows the most recent ones.
+
+use std::{collections::VecDeque, sync::Arc};
+
+use apricot::{app::App, rectangle::Rectangle};
+use hecs::{Entity, World};
+
+use super::{
+    button::{Event, EventQueue},
+    rich_text::TextLayout,
+    text_metrics::TextMetrics,
+};
+
+/// How many notifications the log keeps before forgetting the oldest
+const MAX_LOG_LEN: usize = 200;
+/// How many notifications the HUD shows normally
+const FEED_LINES: usize = 5;
+/// How many notifications the HUD shows when the log is expanded
+const EXPANDED_LINES: usize = 20;
+/// How wide the feed is, in pixels at a UI scale of 1. Longer notifications run past it, but only this much can be clicked
+const FEED_WIDTH: f32 = 400.0;
+
+/// How important a notification is, which decides its color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Success,
+    Warning,
+}
+
+impl Severity {
+    /// The rich text color the notification is drawn in
+    fn color(&self) -> &'static str {
+        match self {
+            Severity::Info => "white",
+            Severity::Success => "green",
+            Severity::Warning => "yellow",
+        }
+    }
+}
+
+/// What a notification is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    /// A new turn started
+    TurnStarted,
+    /// A date scheduled on the calendar came around
+    Milestone,
+    /// A star system was entered for the first time
+    SystemExplored,
+    /// The game was written to the save file
+    GameSaved,
+    /// Writing the save file failed
+    SaveFailed,
+    /// A colony finished a project in its construction queue
+    ConstructionComplete,
+    /// A turn report was written to a text file
+    ReportExported,
+    /// Writing a turn report to a text file failed
+    ExportFailed,
+}
+
+impl NotificationKind {
+    /// How important notifications of this kind are
+    pub fn severity(&self) -> Severity {
+        match self {
+            NotificationKind::TurnStarted
+            | NotificationKind::GameSaved
+            | NotificationKind::ReportExported => Severity::Info,
+            NotificationKind::Milestone
+            | NotificationKind::SystemExplored
+            | NotificationKind::ConstructionComplete => Severity::Success,
+            NotificationKind::SaveFailed | NotificationKind::ExportFailed => Severity::Warning,
+        }
+    }
+}
+
+/// Something that happened, to tell the player about
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    /// The turn the notification was posted on
+    pub turn: usize,
+    /// What happened, which decides how important the notification is
+    pub kind: NotificationKind,
+    pub text: String,
+    /// The body to select when the notification is clicked, if any
+    pub target: Option<Entity>,
+}
+
+/// The log of every notification, shown as a feed in the corner of the HUD. Clicking a notification with a target
+/// selects the target
+pub struct NotificationFeed {
+    /// The bottom-left corner of the feed. The newest notification is at the bottom, older ones stack upward
+    position: nalgebra_glm::Vec2,
+    /// Every notification, oldest first
+    log: VecDeque<Notification>,
+    /// Whether the feed shows more of the log than usual, with the turn of each notification
+    pub expanded: bool,
+    /// The size of the font the feed's text is laid out with
+    metrics: TextMetrics,
+    /// The event queue that stores events triggered by this feed
+    event_queue: Arc<EventQueue>,
+}
+
+impl NotificationFeed {
+    /// Creates a new, empty feed
+    pub fn new(
+        position: nalgebra_glm::Vec2,
+        metrics: TextMetrics,
+        event_queue: Arc<EventQueue>,
+    ) -> Self {
+        Self {
+            position,
+            log: VecDeque::new(),
+            expanded: false,
+            metrics,
+            event_queue,
+        }
+    }
+
+    /// Moves the feed, for when the window is resized
+    pub fn set_position(&mut self, position: nalgebra_glm::Vec2) {
+        self.position = position;
+    }
+
+    /// Adds a notification to the log
+    pub fn post(
+        &mut self,
+        turn: usize,
+        kind: NotificationKind,
+        text: String,
+        target: Option<Entity>,
+    ) {
+        if self.log.len() == MAX_LOG_LEN {
+            self.log.pop_front();
+        }
+        self.log.push_back(Notification {
+            turn,
+            kind,
+            text,
+            target,
+        });
+    }
+
+    /// Every notification posted on a turn, oldest first
+    pub fn turn_log(&self, turn: usize) -> Vec<Notification> {
+        self.log
+            .iter()
+            .filter(|notification| notification.turn == turn)
+            .cloned()
+            .collect()
+    }
+
+    /// Checks if a notification is clicked, and selects its target if it still exists
+    pub fn update(&mut self, app: &App, world: &World) {
+        if !app.mouse_left_clicked {
+            return;
+        }
+        if let Some(target) = self.hovered_target(app, world) {
+            self.event_queue.push(Event::BodyClicked(target));
+        }
+    }
+
+    /// Renders the most recent notifications to the screen
+    pub fn render(&self, app: &App, world: &World, program_2d_id: u32) {
+        let hovered = self.hovered_row(app, world);
+        for (i, notification) in self.visible().enumerate() {
+            let marker = if hovered == Some(i) { ">" } else { " " };
+            let turn = if self.expanded {
+                format!("[color=grey]turn {}:[/color] ", notification.turn)
+            } else {
+                String::new()
+            };
+            let markup = format!(
+                "{} {}[color={}]{}[/color]",
+                marker,
+                turn,
+                notification.kind.severity().color(),
+                // Notification text is plain, so any brackets in it mustn't be read as markup
+                notification.text.replace('[', "[[")
+            );
+            TextLayout::new(&markup, None, self.metrics).draw(app, program_2d_id, self.row_pos(i));
+        }
+    }
+
+    /// The notifications shown on the HUD, newest first
+    fn visible(&self) -> impl Iterator<Item = &Notification> {
+        let lines = if self.expanded {
+            EXPANDED_LINES
+        } else {
+            FEED_LINES
+        };
+        self.log.iter().rev().take(lines)
+    }
+
+    /// The row under the mouse, if its notification has a target that still exists
+    fn hovered_row(&self, app: &App, world: &World) -> Option<usize> {
+        self.visible().enumerate().position(|(i, notification)| {
+            notification
+                .target
+                .is_some_and(|target| world.contains(target))
+                && self.row_rect(i).contains_point(&app.mouse_pos)
+        })
+    }
+
+    /// The target of the notification under the mouse, if it still exists
+    fn hovered_target(&self, app: &App, world: &World) -> Option<Entity> {
+        let row = self.hovered_row(app, world)?;
+        self.visible().nth(row)?.target
+    }
+
+    /// The top-left corner of the `i`th newest notification's row on the screen
+    fn row_pos(&self, i: usize) -> nalgebra_glm::Vec2 {
+        nalgebra_glm::vec2(
+            self.position.x,
+            self.position.y - self.metrics.line_height * (i + 1) as f32,
+        )
+    }
+
+    /// The rectangle that the `i`th newest notification's row covers on the screen
+    fn row_rect(&self, i: usize) -> Rectangle {
+        let pos = self.row_pos(i);
+        Rectangle::new(
+            pos.x,
+            pos.y,
+            self.metrics.scale(FEED_WIDTH),
+            self.metrics.line_height,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed() -> NotificationFeed {
+        NotificationFeed::new(
+            nalgebra_glm::vec2(0.0, 600.0),
+            TextMetrics::new(16, 1.0),
+            Arc::new(EventQueue::new()),
+        )
+    }
+
+    /// The text of the notifications shown on the HUD, newest first
+    fn visible_text(feed: &NotificationFeed) -> Vec<String> {
+        feed.visible()
+            .map(|notification| notification.text.clone())
+            .collect()
+    }
+
+    #[test]
+    fn severity_comes_from_the_kind() {
+        assert_eq!(NotificationKind::GameSaved.severity(), Severity::Info);
+        assert_eq!(
+            NotificationKind::SystemExplored.severity(),
+            Severity::Success
+        );
+        assert_eq!(NotificationKind::SaveFailed.severity(), Severity::Warning);
+    }
+
+    #[test]
+    fn turn_log_has_only_that_turns_notifications() {
+        let mut feed = feed();
+        feed.post(1, NotificationKind::TurnStarted, "turn 1".to_string(), None);
+        feed.post(2, NotificationKind::TurnStarted, "turn 2".to_string(), None);
+        feed.post(2, NotificationKind::GameSaved, "saved".to_string(), None);
+        let log = feed.turn_log(2);
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].text, "turn 2");
+        assert_eq!(log[1].kind, NotificationKind::GameSaved);
+        assert!(feed.turn_log(3).is_empty());
+    }
+
+    #[test]
+    fn the_oldest_notifications_are_forgotten() {
+        let mut feed = feed();
+        for turn in 0..MAX_LOG_LEN + 3 {
+            feed.post(turn, NotificationKind::TurnStarted, turn.to_string(), None);
+        }
+        assert_eq!(feed.log.len(), MAX_LOG_LEN);
+        assert_eq!(feed.log.front().unwrap().turn, 3);
+        assert!(feed.turn_log(2).is_empty());
+        assert_eq!(feed.turn_log(MAX_LOG_LEN + 2).len(), 1);
+    }
+
+    #[test]
+    fn the_feed_shows_the_newest_notifications_first() {
+        let mut feed = feed();
+        for turn in 0..EXPANDED_LINES + 1 {
+            feed.post(turn, NotificationKind::TurnStarted, turn.to_string(), None);
+        }
+        let visible = visible_text(&feed);
+        assert_eq!(visible.len(), FEED_LINES);
+        assert_eq!(visible[0], EXPANDED_LINES.to_string());
+
+        feed.expanded = true;
+        let visible = visible_text(&feed);
+        assert_eq!(visible.len(), EXPANDED_LINES);
+        assert_eq!(visible[EXPANDED_LINES - 1], "1");
+    }
+
+    #[test]
+    fn older_notifications_stack_upward() {
+        let feed = feed();
+        let line_height = feed.metrics.line_height;
+        assert_eq!(
+            feed.row_pos(0),
+            nalgebra_glm::vec2(0.0, 600.0 - line_height)
+        );
+        assert_eq!(
+            feed.row_pos(2),
+            nalgebra_glm::vec2(0.0, 600.0 - 3.0 * line_height)
+        );
+    }
+}
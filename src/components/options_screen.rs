@@ -0,0 +1,104 @@
+//! This module is responsible for defining the options screen, which lists each setting and lets the user step it up
+//! or down
+
+use apricot::{app::App, rectangle::Rectangle};
+
+use super::{
+    settings::{Setting, Settings, SETTINGS_PATH},
+    text_metrics::TextMetrics,
+};
+
+/// How many characters wide the setting name column is
+const LABEL_COLUMN_WIDTH: usize = 24;
+/// How many characters wide the value column is
+const VALUE_COLUMN_WIDTH: usize = 10;
+
+/// Screen that lists each setting's value, with arrows on either side to step it down or up
+pub struct OptionsScreen {
+    /// The top-left corner of the screen's text
+    position: nalgebra_glm::Vec2,
+    /// Whether the screen is shown
+    pub open: bool,
+    /// The size of the font the screen's text is laid out with
+    metrics: TextMetrics,
+}
+
+impl OptionsScreen {
+    /// Creates a new, closed options screen
+    pub fn new(position: nalgebra_glm::Vec2, metrics: TextMetrics) -> Self {
+        Self {
+            position,
+            open: false,
+            metrics,
+        }
+    }
+
+    /// Checks if any setting's arrows are clicked. Changed settings are saved to the settings file right away
+    pub fn update(&mut self, app: &App, settings: &mut Settings) {
+        if !app.mouse_left_clicked {
+            return;
+        }
+        for (i, setting) in Setting::ALL.iter().enumerate() {
+            for up in [false, true] {
+                if self.arrow_rect(i, up).contains_point(&app.mouse_pos) {
+                    settings.set(*setting, setting.step(settings.get(*setting), up));
+                    if let Err(err) = settings.save(SETTINGS_PATH) {
+                        println!("could not save settings: {}", err);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Renders the options screen to the screen
+    pub fn render(&self, app: &App, settings: &Settings) {
+        app.renderer.draw_text(
+            self.position,
+            "options - click the arrows to change a setting",
+        );
+        for (i, setting) in Setting::ALL.iter().enumerate() {
+            let restart_note = if setting.needs_restart() {
+                "  (after restart)"
+            } else {
+                ""
+            };
+            app.renderer.draw_text(
+                self.line_pos(i),
+                format!(
+                    "{:label_width$}< {:^value_width$} >{}",
+                    setting.label(),
+                    setting.display(settings.get(*setting)),
+                    restart_note,
+                    label_width = LABEL_COLUMN_WIDTH,
+                    value_width = VALUE_COLUMN_WIDTH
+                )
+                .as_str(),
+            );
+        }
+    }
+
+    /// The top-left corner of the `i`th setting's line on the screen, below the title
+    fn line_pos(&self, i: usize) -> nalgebra_glm::Vec2 {
+        nalgebra_glm::vec2(
+            self.position.x,
+            self.position.y + self.metrics.line_height * (i + 2) as f32,
+        )
+    }
+
+    /// The rectangle that the down or up arrow of the `i`th setting covers on the screen. Padded by half a character
+    /// on each side, so the arrows are easier to hit
+    fn arrow_rect(&self, i: usize, up: bool) -> Rectangle {
+        let pos = self.line_pos(i);
+        let column = if up {
+            LABEL_COLUMN_WIDTH + VALUE_COLUMN_WIDTH + 3
+        } else {
+            LABEL_COLUMN_WIDTH
+        };
+        Rectangle::new(
+            pos.x + self.metrics.char_width * (column as f32 - 0.5),
+            pos.y,
+            self.metrics.char_width * 2.0,
+            self.metrics.line_height,
+        )
+    }
+}
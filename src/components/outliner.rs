@@ -0,0 +1,207 @@
+//! This module is responsible for defining the outliner, a tree panel of every planetary body in the system
+
+use std::{collections::HashSet, sync::Arc};
+
+use apricot::{app::App, rectangle::Rectangle};
+use hecs::{Entity, World};
+
+use super::{
+    button::{Event, EventQueue},
+    input::{Action, InputMap},
+    planet::Planet,
+    text_metrics::TextMetrics,
+};
+
+/// How many characters each level of the tree is indented by
+const INDENT: usize = 2;
+
+/// A single visible row of the tree
+struct OutlinerRow {
+    /// The body this row represents
+    entity: Entity,
+    /// The body this body orbits, if it is in the tree
+    parent: Option<Entity>,
+    /// How deep in the tree the row is, the star is at depth 0
+    depth: usize,
+    /// The name of the body
+    name: String,
+    /// Whether there are bodies orbiting this body
+    has_children: bool,
+}
+
+/// Panel that lists the body hierarchy (star → planets → moons), so that bodies can be selected by clicking on them or
+/// by navigating the tree with the arrow keys
+pub struct Outliner {
+    /// The top-left corner of the panel
+    position: nalgebra_glm::Vec2,
+    /// The rows currently visible in the panel, rebuilt every update
+    rows: Vec<OutlinerRow>,
+    /// Bodies whose children are hidden
+    collapsed: HashSet<Entity>,
+    /// The size of the font the panel's text is laid out with
+    metrics: TextMetrics,
+    /// The event queue that stores events triggered by this panel
+    event_queue: Arc<EventQueue>,
+}
+
+impl Outliner {
+    /// Creates a new outliner with every body expanded
+    pub fn new(
+        position: nalgebra_glm::Vec2,
+        metrics: TextMetrics,
+        event_queue: Arc<EventQueue>,
+    ) -> Self {
+        Self {
+            position,
+            rows: vec![],
+            collapsed: HashSet::new(),
+            metrics,
+            event_queue,
+        }
+    }
+
+    /// Moves the outliner, for when the window is resized
+    pub fn set_position(&mut self, position: nalgebra_glm::Vec2) {
+        self.position = position;
+    }
+
+    /// Rebuilds the tree, and handles clicks and the outliner navigation actions
+    pub fn update(&mut self, app: &App, input: &InputMap, world: &World, selected: Entity) {
+        self.rows = self.build_rows(world);
+
+        if app.mouse_left_clicked {
+            let clicked =
+                (0..self.rows.len()).find(|i| self.row_rect(*i).contains_point(&app.mouse_pos));
+            if let Some(i) = clicked {
+                let (entity, has_children) = (self.rows[i].entity, self.rows[i].has_children);
+                let pos = self.row_pos(i);
+                let toggle_rect = Rectangle::new(
+                    pos.x,
+                    pos.y,
+                    self.metrics.char_width * 2.0,
+                    self.metrics.line_height,
+                );
+                if has_children && toggle_rect.contains_point(&app.mouse_pos) {
+                    self.toggle(entity);
+                } else {
+                    self.event_queue.push(Event::BodyClicked(entity));
+                }
+            }
+        }
+
+        let up = input.pressed(Action::OutlinerUp);
+        let down = input.pressed(Action::OutlinerDown);
+        let left = input.pressed(Action::OutlinerCollapse);
+        let right = input.pressed(Action::OutlinerExpand);
+        let Some(current) = self.rows.iter().position(|row| row.entity == selected) else {
+            return;
+        };
+        let row = &self.rows[current];
+        if up && current > 0 {
+            self.event_queue
+                .push(Event::BodyClicked(self.rows[current - 1].entity));
+        } else if down && current + 1 < self.rows.len() {
+            self.event_queue
+                .push(Event::BodyClicked(self.rows[current + 1].entity));
+        } else if left {
+            if row.has_children && !self.collapsed.contains(&row.entity) {
+                self.collapsed.insert(row.entity);
+            } else if let Some(parent) = row.parent {
+                self.event_queue.push(Event::BodyClicked(parent));
+            }
+        } else if right && row.has_children {
+            if self.collapsed.contains(&row.entity) {
+                self.collapsed.remove(&row.entity);
+            } else if let Some(child) = self.rows.get(current + 1) {
+                self.event_queue.push(Event::BodyClicked(child.entity));
+            }
+        }
+    }
+
+    /// Renders the outliner to the screen
+    pub fn render(&self, app: &App, selected: Entity) {
+        for (i, row) in self.rows.iter().enumerate() {
+            let toggle = if !row.has_children {
+                "  "
+            } else if self.collapsed.contains(&row.entity) {
+                "+ "
+            } else {
+                "- "
+            };
+            let marker = if row.entity == selected { "> " } else { "" };
+            app.renderer.draw_text(
+                self.row_pos(i),
+                format!("{}{}{}", toggle, marker, row.name).as_str(),
+            );
+        }
+    }
+
+    /// Expands a collapsed body, or collapses an expanded one
+    fn toggle(&mut self, entity: Entity) {
+        if !self.collapsed.remove(&entity) {
+            self.collapsed.insert(entity);
+        }
+    }
+
+    /// The top-left corner of the `i`th row of the panel on the screen
+    fn row_pos(&self, i: usize) -> nalgebra_glm::Vec2 {
+        nalgebra_glm::vec2(
+            self.position.x + self.metrics.char_width * (INDENT * self.rows[i].depth) as f32,
+            self.position.y + self.metrics.line_height * i as f32,
+        )
+    }
+
+    /// The rectangle that the `i`th row of the panel covers on the screen
+    fn row_rect(&self, i: usize) -> Rectangle {
+        let pos = self.row_pos(i);
+        Rectangle::new(
+            pos.x,
+            pos.y,
+            self.metrics.char_width * (self.rows[i].name.len() + 4) as f32,
+            self.metrics.line_height,
+        )
+    }
+
+    /// Walks the body hierarchy depth-first, skipping the children of collapsed bodies
+    fn build_rows(&self, world: &World) -> Vec<OutlinerRow> {
+        // Group every body by its parent, innermost orbit first
+        let mut bodies: Vec<(Entity, Entity, f32, String)> = world
+            .query::<&Planet>()
+            .iter()
+            .map(|(entity, planet)| {
+                (
+                    entity,
+                    planet.parent_planet_id,
+                    planet.orbital_radius,
+                    planet.name.clone(),
+                )
+            })
+            .collect();
+        bodies.sort_by(|a, b| a.2.total_cmp(&b.2));
+        let is_body = |entity: Entity| bodies.iter().any(|body| body.0 == entity);
+
+        let mut rows = vec![];
+        let mut stack: Vec<(Entity, Option<Entity>, usize, String)> = bodies
+            .iter()
+            .rev()
+            .filter(|body| !is_body(body.1))
+            .map(|body| (body.0, None, 0, body.3.clone()))
+            .collect();
+        while let Some((entity, parent, depth, name)) = stack.pop() {
+            let children: Vec<_> = bodies.iter().filter(|body| body.1 == entity).collect();
+            rows.push(OutlinerRow {
+                entity,
+                parent,
+                depth,
+                name,
+                has_children: !children.is_empty(),
+            });
+            if !self.collapsed.contains(&entity) {
+                for child in children.iter().rev() {
+                    stack.push((child.0, Some(entity), depth + 1, child.3.clone()));
+                }
+            }
+        }
+        rows
+    }
+}
@@ -0,0 +1,267 @@
+//! This module is responsible for planning routes between star systems along the hyperlane graph. It only works on
+//! system positions and lanes, so it doesn't depend on the World or the renderer.
+
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+/// How many times more a lane costs when either end is in hostile territory
+const HOSTILE_COST_MULTIPLIER: f32 = 4.0;
+/// How many times more a lane costs when either end hasn't been explored, since what's there is unknown
+const UNEXPLORED_COST_MULTIPLIER: f32 = 1.5;
+/// How many times less a lane costs when either end is defended by the empire's stations, since fleets are safer there
+const DEFENDED_COST_MULTIPLIER: f32 = 0.8;
+
+/// What the empire knows about a star system, for weighing routes through it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SystemKnowledge {
+    /// Whether the system has been visited, and isn't under the fog of war
+    pub explored: bool,
+    /// Whether the system is controlled by someone unfriendly
+    pub hostile: bool,
+    /// The total defense of the empire's stations in the system
+    pub defense: u32,
+}
+
+/// A planned route between two star systems
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route {
+    /// The systems along the route, starting with the system the route leaves from and ending with its destination
+    pub systems: Vec<usize>,
+    /// The turn, counted from departure, that each system in `systems` is reached
+    pub arrival_turns: Vec<usize>,
+    /// The weighted cost of the route, which the route is the cheapest by
+    pub cost: f32,
+}
+
+impl Route {
+    /// How many turns it takes to reach the destination
+    pub fn eta(&self) -> usize {
+        *self.arrival_turns.last().unwrap()
+    }
+}
+
+/// Entry in the open set, ordered so that the lowest estimated total cost is popped first
+struct OpenEntry {
+    /// Cost so far plus the heuristic to the goal
+    estimate: f32,
+    /// Index of the system this entry is for
+    system: usize,
+}
+
+impl PartialEq for OpenEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OpenEntry {}
+
+impl PartialOrd for OpenEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .total_cmp(&self.estimate)
+            .then_with(|| other.system.cmp(&self.system))
+    }
+}
+
+/// Finds the cheapest route from `start` to `goal` with A*, or None if they aren't connected. Lanes cost their
+/// length, more through hostile or unexplored systems and less through defended ones. `speed` is how far along lanes
+/// a fleet travels in one turn, and is used for the route's arrival turns.
+pub fn find_route(
+    positions: &[nalgebra_glm::Vec3],
+    lanes: &[(usize, usize)],
+    knowledge: &[SystemKnowledge],
+    start: usize,
+    goal: usize,
+    speed: f32,
+) -> Option<Route> {
+    let mut adjacency: Vec<Vec<usize>> = vec![vec![]; positions.len()];
+    for (a, b) in lanes.iter() {
+        adjacency[*a].push(*b);
+        adjacency[*b].push(*a);
+    }
+
+    // No lane costs less than its length times the defended discount, so straight-line distance scaled by that
+    // discount never overestimates
+    let heuristic = |system: usize| {
+        nalgebra_glm::distance(&positions[system], &positions[goal]) * DEFENDED_COST_MULTIPLIER
+    };
+
+    let mut costs = vec![f32::INFINITY; positions.len()];
+    let mut came_from: Vec<Option<usize>> = vec![None; positions.len()];
+    let mut open = BinaryHeap::new();
+    costs[start] = 0.0;
+    open.push(OpenEntry {
+        estimate: heuristic(start),
+        system: start,
+    });
+
+    while let Some(OpenEntry { estimate, system }) = open.pop() {
+        if system == goal {
+            break;
+        }
+        if estimate > costs[system] + heuristic(system) {
+            // A cheaper way to this system was already expanded
+            continue;
+        }
+        for neighbor in adjacency[system].iter() {
+            let cost = costs[system] + lane_cost(positions, knowledge, system, *neighbor);
+            if cost < costs[*neighbor] {
+                costs[*neighbor] = cost;
+                came_from[*neighbor] = Some(system);
+                open.push(OpenEntry {
+                    estimate: cost + heuristic(*neighbor),
+                    system: *neighbor,
+                });
+            }
+        }
+    }
+
+    if costs[goal].is_infinite() {
+        return None;
+    }
+
+    let mut systems = vec![goal];
+    while let Some(prev) = came_from[*systems.last().unwrap()] {
+        systems.push(prev);
+    }
+    systems.reverse();
+
+    let mut distance = 0.0;
+    let mut arrival_turns = vec![0];
+    for leg in systems.windows(2) {
+        distance += nalgebra_glm::distance(&positions[leg[0]], &positions[leg[1]]);
+        arrival_turns.push((distance / speed).ceil() as usize);
+    }
+
+    Some(Route {
+        systems,
+        arrival_turns,
+        cost: costs[goal],
+    })
+}
+
+/// The cost of travelling along the lane between two systems
+fn lane_cost(
+    positions: &[nalgebra_glm::Vec3],
+    knowledge: &[SystemKnowledge],
+    from: usize,
+    to: usize,
+) -> f32 {
+    let mut cost = nalgebra_glm::distance(&positions[from], &positions[to]);
+    if knowledge[from].hostile || knowledge[to].hostile {
+        cost *= HOSTILE_COST_MULTIPLIER;
+    }
+    if !knowledge[from].explored || !knowledge[to].explored {
+        cost *= UNEXPLORED_COST_MULTIPLIER;
+    }
+    if knowledge[from].defense > 0 || knowledge[to].defense > 0 {
+        cost *= DEFENDED_COST_MULTIPLIER;
+    }
+    cost
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPLORED: SystemKnowledge = SystemKnowledge {
+        explored: true,
+        hostile: false,
+        defense: 0,
+    };
+
+    /// A square of systems 0-1-2-3 with 100 unit sides, plus a far off system 4 with no lanes
+    fn square() -> (Vec<nalgebra_glm::Vec3>, Vec<(usize, usize)>) {
+        let positions = vec![
+            nalgebra_glm::vec3(0.0, 0.0, 0.0),
+            nalgebra_glm::vec3(100.0, 0.0, 0.0),
+            nalgebra_glm::vec3(100.0, 100.0, 0.0),
+            nalgebra_glm::vec3(0.0, 100.0, 0.0),
+            nalgebra_glm::vec3(500.0, 500.0, 0.0),
+        ];
+        let lanes = vec![(0, 1), (1, 2), (2, 3), (3, 0)];
+        (positions, lanes)
+    }
+
+    #[test]
+    fn route_to_self_is_empty() {
+        let (positions, lanes) = square();
+        let route = find_route(&positions, &lanes, &[EXPLORED; 5], 2, 2, 10.0).unwrap();
+        assert_eq!(route.systems, vec![2]);
+        assert_eq!(route.eta(), 0);
+        assert_eq!(route.cost, 0.0);
+    }
+
+    #[test]
+    fn unconnected_systems_have_no_route() {
+        let (positions, lanes) = square();
+        assert_eq!(
+            find_route(&positions, &lanes, &[EXPLORED; 5], 0, 4, 10.0),
+            None
+        );
+    }
+
+    #[test]
+    fn follows_lanes_instead_of_straight_line() {
+        let (positions, lanes) = square();
+        let route = find_route(&positions, &lanes, &[EXPLORED; 5], 0, 2, 10.0).unwrap();
+        assert_eq!(route.systems.len(), 3);
+        assert_eq!(route.systems[0], 0);
+        assert_eq!(route.systems[2], 2);
+        assert_eq!(route.cost, 200.0);
+    }
+
+    #[test]
+    fn avoids_hostile_territory() {
+        let (positions, lanes) = square();
+        let mut knowledge = [EXPLORED; 5];
+        knowledge[1].hostile = true;
+        let route = find_route(&positions, &lanes, &knowledge, 0, 2, 10.0).unwrap();
+        assert_eq!(route.systems, vec![0, 3, 2]);
+    }
+
+    #[test]
+    fn prefers_explored_systems() {
+        let (positions, lanes) = square();
+        let mut knowledge = [EXPLORED; 5];
+        knowledge[3].explored = false;
+        let route = find_route(&positions, &lanes, &knowledge, 0, 2, 10.0).unwrap();
+        assert_eq!(route.systems, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn prefers_defended_systems() {
+        let (positions, lanes) = square();
+        let mut knowledge = [EXPLORED; 5];
+        knowledge[3].defense = 25;
+        let route = find_route(&positions, &lanes, &knowledge, 0, 2, 10.0).unwrap();
+        assert_eq!(route.systems, vec![0, 3, 2]);
+        assert_eq!(route.cost, 200.0 * DEFENDED_COST_MULTIPLIER);
+    }
+
+    #[test]
+    fn goes_through_hostile_territory_when_it_must() {
+        let (positions, lanes) = square();
+        let mut knowledge = [EXPLORED; 5];
+        knowledge[1].hostile = true;
+        let route = find_route(&positions, &lanes, &knowledge, 0, 1, 10.0).unwrap();
+        assert_eq!(route.systems, vec![0, 1]);
+        assert_eq!(route.cost, 100.0 * HOSTILE_COST_MULTIPLIER);
+    }
+
+    #[test]
+    fn eta_counts_turns_along_the_route() {
+        let (positions, lanes) = square();
+        let route = find_route(&positions, &lanes, &[EXPLORED; 5], 0, 2, 30.0).unwrap();
+        // 100 units takes 4 turns at 30 units a turn, 200 units takes 7
+        assert_eq!(route.arrival_turns, vec![0, 4, 7]);
+        assert_eq!(route.eta(), 7);
+    }
+}
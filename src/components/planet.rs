@@ -3,20 +3,25 @@
 
 use apricot::{
     bvh::{BVHNodeId, BVH},
-    render_core::{LinePathComponent, ModelComponent, RenderContext, TextureId},
+    render_core::{LinePathComponent, MeshId, ModelComponent, RenderContext, TextureId},
 };
 use hecs::{Entity, World};
 
+use super::lod::MeshLod;
+
 pub struct Planet {
+    /// The body this body orbits, or `Entity::DANGLING` to orbit the system's barycenter
     pub parent_planet_id: Entity,
     pub tier: u32,
     pub body_radius: f32,
     pub orbital_radius: f32,
+    /// Angle offset along the orbit, in radians, so that bodies sharing an orbit (like binary stars) can be spread out
+    pub orbital_phase: f32,
     pub orbital_time_years: f32,
     pub day_time_years: f32,
     pub rotation: f32,
     pub bvh_node_id: BVHNodeId,
-    pub name: &'static str,
+    pub name: String,
 }
 
 impl Planet {
@@ -24,38 +29,76 @@ impl Planet {
         world: &mut World,
         renderer: &RenderContext,
         bvh: &mut BVH<Entity>,
+        orbit_segments: usize,
 
         gaseous: bool,
         parent_planet_id: Entity,
         tier: u32,
         body_radius: f32,
         orbital_radius: f32,
+        orbital_phase: f32,
         orbital_time_years: f32,
         day_time_years: f32,
         texture_id: TextureId,
-        name: &'static str,
+        name: String,
     ) -> Entity {
-        let planet_mesh = if gaseous {
-            renderer.get_mesh_id_from_name("uv").unwrap()
-        } else {
-            renderer.get_mesh_id_from_name("ico").unwrap()
-        };
+        // Gaseous bodies are smooth shaded, rocky bodies are faceted
+        let lod = MeshLod::new(renderer, gaseous, texture_id);
+        let planet_entity = Self::with_mesh(
+            world,
+            renderer,
+            bvh,
+            orbit_segments,
+            lod.initial_mesh(),
+            parent_planet_id,
+            tier,
+            body_radius,
+            orbital_radius,
+            orbital_phase,
+            orbital_time_years,
+            day_time_years,
+            texture_id,
+            name,
+        );
+        world.insert_one(planet_entity, lod).unwrap();
+        planet_entity
+    }
+
+    /// Spawns a body drawn with a fixed mesh, instead of icospheres that change detail. Used for artificial bodies,
+    /// like orbital stations
+    pub fn with_mesh(
+        world: &mut World,
+        renderer: &RenderContext,
+        bvh: &mut BVH<Entity>,
+        orbit_segments: usize,
 
+        mesh_id: MeshId,
+        parent_planet_id: Entity,
+        tier: u32,
+        body_radius: f32,
+        orbital_radius: f32,
+        orbital_phase: f32,
+        orbital_time_years: f32,
+        day_time_years: f32,
+        texture_id: TextureId,
+        name: String,
+    ) -> Entity {
         let position = nalgebra_glm::vec3(0., 0., 0.);
         let scale_vec = nalgebra_glm::vec3(body_radius, body_radius, body_radius);
 
         let planet_entity = world.spawn((ModelComponent::new(
-            planet_mesh,
-            texture_id,
-            position,
-            scale_vec,
+            mesh_id, texture_id, position, scale_vec,
         ),));
 
         if orbital_radius > 1.0 {
             world
                 .insert(
                     planet_entity,
-                    (LinePathComponent::from_orbit(orbital_radius, 0.0, 1024),),
+                    (LinePathComponent::from_orbit(
+                        orbital_radius,
+                        0.0,
+                        orbit_segments,
+                    ),),
                 )
                 .unwrap()
         }
@@ -63,7 +106,7 @@ impl Planet {
         let bvh_node_id = bvh.insert(
             planet_entity,
             renderer
-                .get_mesh_aabb(planet_mesh)
+                .get_mesh_aabb(mesh_id)
                 .scale(scale_vec)
                 .translate(position),
         );
@@ -76,6 +119,7 @@ impl Planet {
                     tier,
                     body_radius,
                     orbital_radius,
+                    orbital_phase,
                     orbital_time_years,
                     day_time_years,
                     rotation: 0.0,
@@ -0,0 +1,112 @@
+//! This module is responsible for projecting points in the 3d world onto the screen, for drawing 2d overlays over bodies
+
+use apricot::render_core::ModelComponent;
+use hecs::{Entity, World};
+
+use super::planet::Planet;
+
+/// Bodies that appear smaller than this radius on the screen, in pixels, can still be picked within this radius
+const MIN_PICK_RADIUS: f32 = 6.0;
+
+/// A snapshot of the 3d camera, used to figure out where things in the world appear on the screen
+pub struct ScreenProjection {
+    /// Where the camera is in the world
+    pub camera_pos: nalgebra_glm::Vec3,
+    /// The camera's view-projection matrix
+    view_proj: nalgebra_glm::Mat4,
+    /// Vertical field of view of the camera, in radians
+    fov: f32,
+    /// The size of the window, in pixels
+    window_size: nalgebra_glm::Vec2,
+}
+
+impl ScreenProjection {
+    /// Creates a new projection for a perspective camera at `camera_pos` looking at `lookat`, with the z axis up
+    pub fn new(
+        camera_pos: nalgebra_glm::Vec3,
+        lookat: nalgebra_glm::Vec3,
+        fov: f32,
+        far: f32,
+        window_size: nalgebra_glm::Vec2,
+    ) -> Self {
+        let view_proj = nalgebra_glm::perspective(window_size.x / window_size.y, fov, 0.1, far)
+            * nalgebra_glm::look_at(&camera_pos, &lookat, &nalgebra_glm::vec3(0.0, 0.0, 1.0));
+        Self {
+            camera_pos,
+            view_proj,
+            fov,
+            window_size,
+        }
+    }
+
+    /// Where a point in the world appears on the screen in pixels, or None if it is behind the camera
+    pub fn project(&self, position: nalgebra_glm::Vec3) -> Option<nalgebra_glm::Vec2> {
+        let clip = self.view_proj * nalgebra_glm::vec4(position.x, position.y, position.z, 1.0);
+        if clip.w <= 0.0 {
+            return None;
+        }
+        let ndc = clip.xyz() / clip.w;
+        Some(nalgebra_glm::vec2(
+            (ndc.x + 1.0) * 0.5 * self.window_size.x,
+            (1.0 - ndc.y) * 0.5 * self.window_size.y,
+        ))
+    }
+
+    /// Roughly how many pixels across the radius of a sphere in the world appears on the screen
+    pub fn pixel_radius(&self, position: nalgebra_glm::Vec3, radius: f32) -> f32 {
+        let distance = nalgebra_glm::distance(&self.camera_pos, &position);
+        radius / (distance * (self.fov * 0.5).tan()) * self.window_size.y * 0.5
+    }
+
+    /// Whether any body other than `entity` is between the camera and `position`
+    pub fn is_occluded(
+        &self,
+        entity: Entity,
+        position: nalgebra_glm::Vec3,
+        spheres: &[(Entity, nalgebra_glm::Vec3, f32)],
+    ) -> bool {
+        let distance = nalgebra_glm::distance(&self.camera_pos, &position);
+        let dir = (position - self.camera_pos) / distance;
+        spheres.iter().any(|(other, center, radius)| {
+            if *other == entity {
+                return false;
+            }
+            let t = nalgebra_glm::dot(&(center - self.camera_pos), &dir);
+            t > 0.0
+                && t < distance
+                && nalgebra_glm::distance(&(self.camera_pos + dir * t), center) < *radius
+        })
+    }
+
+    /// The body under a point on the screen, or None if there isn't one. When several bodies are under the point, the
+    /// one closest to the camera wins
+    pub fn pick(
+        &self,
+        screen_pos: nalgebra_glm::Vec2,
+        spheres: &[(Entity, nalgebra_glm::Vec3, f32)],
+    ) -> Option<Entity> {
+        spheres
+            .iter()
+            .filter(|(_entity, center, radius)| {
+                self.project(*center).is_some_and(|projected| {
+                    let pick_radius = self.pixel_radius(*center, *radius).max(MIN_PICK_RADIUS);
+                    nalgebra_glm::distance(&projected, &screen_pos) <= pick_radius
+                })
+            })
+            .min_by(|(_a, a_center, _), (_b, b_center, _)| {
+                let a_distance = nalgebra_glm::distance(&self.camera_pos, a_center);
+                let b_distance = nalgebra_glm::distance(&self.camera_pos, b_center);
+                a_distance.total_cmp(&b_distance)
+            })
+            .map(|(entity, _center, _radius)| *entity)
+    }
+}
+
+/// Collects the position and radius of every body, for checking occlusion
+pub fn body_spheres(world: &World) -> Vec<(Entity, nalgebra_glm::Vec3, f32)> {
+    world
+        .query::<(&ModelComponent, &Planet)>()
+        .iter()
+        .map(|(entity, (model, planet))| (entity, model.get_position(), planet.body_radius))
+        .collect()
+}
@@ -0,0 +1,531 @@
+//! This module is responsible for laying out and drawing rich text, on top of the renderer's single-line plain text.
+//! Rich text is written with markup tags in square brackets:
+//!
+//! - `[b]bold[/b]` and `[i]italic[/i]`, drawn with the bold and italic fonts
+//! - `[color=red]colored[/color]`, with a color name or a hex color like `#ff8800`. Colors nest
+//! - `[icon=body]`, an inline icon, drawn from the texture named `body-icon`
+//! - `[[` for a literal `[`
+//!
+//! Text wraps at spaces to fit a width, and `\n` starts a new line. Unknown tags are kept as plain text, so a typo
+//! shows up on screen instead of silently disappearing.
+
+use apricot::{app::App, rectangle::Rectangle};
+
+use super::{label::set_color, text_metrics::TextMetrics};
+
+/// How far an inline icon moves the text after it along, in characters
+const ICON_ADVANCE: f32 = 2.0;
+/// How big inline icons are drawn, in lines
+const ICON_SIZE: f32 = 0.8;
+/// The size of icon textures, in pixels
+const ICON_TEXTURE_SIZE: f32 = 64.0;
+/// The color text is drawn in when no color is given
+const DEFAULT_COLOR: nalgebra_glm::Vec3 = nalgebra_glm::Vec3::new(1.0, 1.0, 1.0);
+
+/// How a run of text is drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextStyle {
+    pub bold: bool,
+    pub italic: bool,
+    pub color: nalgebra_glm::Vec3,
+}
+
+impl TextStyle {
+    /// The name of the font the style is drawn with
+    fn font_name(&self) -> &'static str {
+        match (self.bold, self.italic) {
+            (false, false) => "font",
+            (true, false) => "font-bold",
+            (false, true) => "font-italic",
+            (true, true) => "font-bold-italic",
+        }
+    }
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            bold: false,
+            italic: false,
+            color: DEFAULT_COLOR,
+        }
+    }
+}
+
+/// A piece of parsed markup
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Text with no spaces or line breaks in it
+    Word(String, TextStyle),
+    Space,
+    LineBreak,
+    /// An inline icon, by name
+    Icon(String),
+}
+
+/// Something drawn at a point in a text layout
+#[derive(Debug, Clone, PartialEq)]
+pub enum Run {
+    Text(String, TextStyle),
+    Icon(String),
+}
+
+/// Rich text broken into lines, with everything positioned relative to the top-left corner
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLayout {
+    /// What to draw, and where relative to the top-left corner
+    pub runs: Vec<(nalgebra_glm::Vec2, Run)>,
+    /// The width of the widest line and the height of all the lines, in pixels
+    pub size: nalgebra_glm::Vec2,
+    /// The size of the font the text was laid out for
+    metrics: TextMetrics,
+}
+
+impl TextLayout {
+    /// Lays out markup for a font size, wrapping it to fit within `max_width` pixels if given
+    pub fn new(markup: &str, max_width: Option<f32>, metrics: TextMetrics) -> Self {
+        let mut builder = LayoutBuilder::new(max_width.unwrap_or(f32::INFINITY), metrics);
+        let mut tokens = tokenize(markup).into_iter().peekable();
+        while let Some(token) = tokens.next() {
+            match token {
+                Token::Word(text, style) => {
+                    // Words can be split across styles, like "[b]10[/b]%", so the pieces are kept together
+                    let mut pieces = vec![(text, style)];
+                    while let Some(Token::Word(_, _)) = tokens.peek() {
+                        let Some(Token::Word(text, style)) = tokens.next() else {
+                            unreachable!();
+                        };
+                        pieces.push((text, style));
+                    }
+                    builder.push_word(pieces);
+                }
+                Token::Space => builder.pending_space = true,
+                Token::LineBreak => builder.break_line(),
+                Token::Icon(name) => builder.push_icon(name),
+            }
+        }
+        builder.finish()
+    }
+
+    /// Draws the laid out text with its top-left corner at `position`
+    pub fn draw(&self, app: &App, program_2d_id: u32, position: nalgebra_glm::Vec2) {
+        self.draw_window(app, program_2d_id, position, 0.0, f32::INFINITY);
+    }
+
+    /// Draws the lines of the laid out text that fit in a window `height` pixels tall, starting `scroll` pixels down
+    /// the text, with the window's top-left corner at `position`
+    pub fn draw_window(
+        &self,
+        app: &App,
+        program_2d_id: u32,
+        position: nalgebra_glm::Vec2,
+        scroll: f32,
+        height: f32,
+    ) {
+        let visible = self.runs.iter().filter(|(offset, _run)| {
+            offset.y >= scroll && offset.y + self.metrics.line_height <= scroll + height
+        });
+        for (offset, run) in visible {
+            let pos = position + offset - nalgebra_glm::vec2(0.0, scroll);
+            match run {
+                Run::Text(text, style) => {
+                    app.renderer.set_font(
+                        app.renderer
+                            .get_font_id_from_name(style.font_name())
+                            .unwrap(),
+                    );
+                    set_color(program_2d_id, style.color);
+                    app.renderer.draw_text(pos, text);
+                }
+                Run::Icon(name) => {
+                    let Some(texture_id) = app
+                        .renderer
+                        .get_texture_id_from_name(format!("{}-icon", name).as_str())
+                    else {
+                        continue;
+                    };
+                    let advance = self.metrics.char_width * ICON_ADVANCE;
+                    let size = self.metrics.line_height * ICON_SIZE;
+                    set_color(program_2d_id, DEFAULT_COLOR);
+                    app.renderer.copy_texture(
+                        Rectangle::new(
+                            pos.x + (advance - size) / 2.0,
+                            pos.y + (self.metrics.line_height - size) / 2.0,
+                            size,
+                            size,
+                        ),
+                        texture_id,
+                        Rectangle::new(0.0, 0.0, ICON_TEXTURE_SIZE, ICON_TEXTURE_SIZE),
+                    );
+                }
+            }
+        }
+        set_color(program_2d_id, DEFAULT_COLOR);
+        app.renderer
+            .set_font(app.renderer.get_font_id_from_name("font").unwrap());
+    }
+}
+
+/// Strips the markup from text, leaving what would be read on screen. Icons are dropped
+pub fn plain_text(markup: &str) -> String {
+    tokenize(markup)
+        .into_iter()
+        .map(|token| match token {
+            Token::Word(text, _style) => text,
+            Token::Space => " ".to_string(),
+            Token::LineBreak => "\n".to_string(),
+            Token::Icon(_) => String::new(),
+        })
+        .collect()
+}
+
+/// Places words and icons on lines, one after another
+struct LayoutBuilder {
+    max_width: f32,
+    metrics: TextMetrics,
+    runs: Vec<(nalgebra_glm::Vec2, Run)>,
+    /// Where the next word goes
+    cursor: nalgebra_glm::Vec2,
+    /// Whether a space comes before the next word, unless the word starts a new line
+    pending_space: bool,
+    /// The width of the widest line so far
+    width: f32,
+}
+
+impl LayoutBuilder {
+    fn new(max_width: f32, metrics: TextMetrics) -> Self {
+        Self {
+            max_width,
+            metrics,
+            runs: vec![],
+            cursor: nalgebra_glm::vec2(0.0, 0.0),
+            pending_space: false,
+            width: 0.0,
+        }
+    }
+
+    /// Moves on to the next line
+    fn break_line(&mut self) {
+        self.cursor = nalgebra_glm::vec2(0.0, self.cursor.y + self.metrics.line_height);
+        self.pending_space = false;
+    }
+
+    /// Makes room for something `width` pixels wide, wrapping to the next line if it doesn't fit on this one
+    fn make_room(&mut self, width: f32) {
+        let space = if self.pending_space {
+            self.metrics.char_width
+        } else {
+            0.0
+        };
+        if self.cursor.x > 0.0 && self.cursor.x + space + width > self.max_width {
+            self.break_line();
+        } else if self.pending_space {
+            self.push_text(" ".to_string(), self.style_before_cursor());
+        }
+        self.pending_space = false;
+    }
+
+    /// Places a word made of differently styled pieces. Words too wide for a line on their own are split
+    fn push_word(&mut self, pieces: Vec<(String, TextStyle)>) {
+        let width = pieces
+            .iter()
+            .map(|(text, _style)| self.metrics.text_width(text))
+            .sum::<f32>();
+        self.make_room(width);
+        for (text, style) in pieces {
+            for c in text.chars() {
+                if self.cursor.x > 0.0 && self.cursor.x + self.metrics.char_width > self.max_width {
+                    self.break_line();
+                }
+                self.push_text(c.to_string(), style);
+            }
+        }
+    }
+
+    /// Places an inline icon
+    fn push_icon(&mut self, name: String) {
+        let advance = self.metrics.char_width * ICON_ADVANCE;
+        self.make_room(advance);
+        self.runs.push((self.cursor, Run::Icon(name)));
+        self.advance(advance);
+    }
+
+    /// Places text at the cursor, joining it onto the last run if that's the same style and on the same line. The
+    /// last run always ends at the cursor, since everything is placed left to right
+    fn push_text(&mut self, text: String, style: TextStyle) {
+        let width = self.metrics.text_width(&text);
+        match self.runs.last_mut() {
+            Some((pos, Run::Text(last_text, last_style)))
+                if *last_style == style && pos.y == self.cursor.y =>
+            {
+                last_text.push_str(&text)
+            }
+            _ => self.runs.push((self.cursor, Run::Text(text, style))),
+        }
+        self.advance(width);
+    }
+
+    /// The style of the text just before the cursor, so spaces match the words around them
+    fn style_before_cursor(&self) -> TextStyle {
+        match self.runs.last() {
+            Some((_pos, Run::Text(_text, style))) => *style,
+            _ => TextStyle::default(),
+        }
+    }
+
+    fn advance(&mut self, width: f32) {
+        self.cursor.x += width;
+        self.width = self.width.max(self.cursor.x);
+    }
+
+    fn finish(self) -> TextLayout {
+        let lines = if self.runs.is_empty() {
+            0.0
+        } else {
+            self.cursor.y / self.metrics.line_height + 1.0
+        };
+        TextLayout {
+            runs: self.runs,
+            size: nalgebra_glm::vec2(self.width, lines * self.metrics.line_height),
+            metrics: self.metrics,
+        }
+    }
+}
+
+/// Breaks markup into words, spaces, line breaks and icons, applying the style tags to the words
+fn tokenize(markup: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut bold = 0;
+    let mut italic = 0;
+    let mut colors = vec![];
+    let mut word = String::new();
+
+    let style = |bold: i32, italic: i32, colors: &[nalgebra_glm::Vec3]| TextStyle {
+        bold: bold > 0,
+        italic: italic > 0,
+        color: colors.last().copied().unwrap_or(DEFAULT_COLOR),
+    };
+
+    let mut rest = markup;
+    while let Some(c) = rest.chars().next() {
+        // Tags change the style, so the word so far is finished in the old style
+        let tag = (c == '[')
+            .then(|| rest[1..].split_once(']'))
+            .flatten()
+            .filter(|(tag, _after)| is_tag(tag));
+        if let Some((tag, after)) = tag {
+            if !word.is_empty() {
+                tokens.push(Token::Word(
+                    std::mem::take(&mut word),
+                    style(bold, italic, &colors),
+                ));
+            }
+            match tag {
+                "b" => bold += 1,
+                // A stray closing tag doesn't eat the next opening one
+                "/b" => bold = (bold - 1).max(0),
+                "i" => italic += 1,
+                "/i" => italic = (italic - 1).max(0),
+                "/color" => {
+                    colors.pop();
+                }
+                _ => {
+                    if let Some(color) = tag.strip_prefix("color=").and_then(parse_color) {
+                        colors.push(color);
+                    } else if let Some(name) = tag.strip_prefix("icon=") {
+                        tokens.push(Token::Icon(name.to_string()));
+                    }
+                }
+            }
+            rest = after;
+            continue;
+        }
+
+        let literal = if rest.starts_with("[[") {
+            rest = &rest[2..];
+            '['
+        } else {
+            rest = &rest[c.len_utf8()..];
+            c
+        };
+        if literal == ' ' || literal == '\n' {
+            if !word.is_empty() {
+                tokens.push(Token::Word(
+                    std::mem::take(&mut word),
+                    style(bold, italic, &colors),
+                ));
+            }
+            tokens.push(if literal == ' ' {
+                Token::Space
+            } else {
+                Token::LineBreak
+            });
+        } else {
+            word.push(literal);
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(Token::Word(word, style(bold, italic, &colors)));
+    }
+    tokens
+}
+
+/// Whether the inside of a pair of square brackets is a known tag
+fn is_tag(tag: &str) -> bool {
+    matches!(tag, "b" | "/b" | "i" | "/i" | "/color")
+        || tag
+            .strip_prefix("color=")
+            .is_some_and(|color| parse_color(color).is_some())
+        || tag
+            .strip_prefix("icon=")
+            .is_some_and(|name| !name.is_empty() && !name.contains(' '))
+}
+
+/// Parses a color name, or a hex color like `#ff8800`
+fn parse_color(color: &str) -> Option<nalgebra_glm::Vec3> {
+    let named = match color {
+        "white" => Some((255, 255, 255)),
+        "grey" | "gray" => Some((150, 150, 150)),
+        "red" => Some((230, 80, 70)),
+        "orange" => Some((240, 150, 50)),
+        "yellow" => Some((240, 220, 90)),
+        "green" => Some((110, 210, 100)),
+        "blue" => Some((100, 160, 240)),
+        _ => None,
+    };
+    let (r, g, b) = named.or_else(|| {
+        let hex = color.strip_prefix('#')?;
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        Some((channel(0)?, channel(2)?, channel(4)?))
+    })?;
+    Some(nalgebra_glm::vec3(r as f32, g as f32, b as f32) / 255.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics() -> TextMetrics {
+        TextMetrics::default()
+    }
+
+    fn words(markup: &str) -> Vec<(String, TextStyle)> {
+        tokenize(markup)
+            .into_iter()
+            .filter_map(|token| match token {
+                Token::Word(text, style) => Some((text, style)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn color(name: &str) -> nalgebra_glm::Vec3 {
+        parse_color(name).unwrap()
+    }
+
+    #[test]
+    fn double_bracket_is_a_literal_bracket() {
+        assert_eq!(plain_text("[[b] and [[1]"), "[b] and [1]");
+        assert_eq!(words("[[b]x")[0].1, TextStyle::default());
+    }
+
+    #[test]
+    fn unknown_tags_are_kept_as_text() {
+        assert_eq!(plain_text("[foo]bar[/foo]"), "[foo]bar[/foo]");
+        assert_eq!(plain_text("[color=nope]x[/color]"), "[color=nope]x");
+        assert_eq!(plain_text("[icon=]x"), "[icon=]x");
+    }
+
+    #[test]
+    fn stray_closing_tags_are_ignored() {
+        let words = words("[/b][b]bold[/b] [/i][/i][i]italic");
+        assert!(words[0].1.bold);
+        assert!(!words[1].1.bold);
+        assert!(words[1].1.italic);
+    }
+
+    #[test]
+    fn styles_nest() {
+        let words = words("[b]a [i]b[/i] c[/b] d");
+        let styles: Vec<(bool, bool)> = words
+            .iter()
+            .map(|(_text, style)| (style.bold, style.italic))
+            .collect();
+        assert_eq!(
+            styles,
+            vec![(true, false), (true, true), (true, false), (false, false)]
+        );
+    }
+
+    #[test]
+    fn colors_nest_and_restore() {
+        let words = words("a [color=red]b [color=#0000ff]c[/color] d[/color] e");
+        let colors: Vec<nalgebra_glm::Vec3> =
+            words.iter().map(|(_text, style)| style.color).collect();
+        assert_eq!(
+            colors,
+            vec![
+                DEFAULT_COLOR,
+                color("red"),
+                nalgebra_glm::vec3(0.0, 0.0, 1.0),
+                color("red"),
+                DEFAULT_COLOR,
+            ]
+        );
+    }
+
+    #[test]
+    fn unmatched_closing_color_keeps_the_default() {
+        assert_eq!(words("[/color]a")[0].1.color, DEFAULT_COLOR);
+    }
+
+    #[test]
+    fn wraps_at_spaces_to_fit_the_width() {
+        let metrics = metrics();
+        // "aaa bbb" fits in exactly 7 characters, "aaa bbb ccc" doesn't
+        let width = metrics.char_width * 7.0;
+        let layout = TextLayout::new("aaa bbb ccc", Some(width), metrics);
+        assert_eq!(layout.size.y, metrics.line_height * 2.0);
+        assert_eq!(layout.size.x, width);
+        let lines: Vec<(f32, String)> = layout
+            .runs
+            .iter()
+            .map(|(pos, run)| match run {
+                Run::Text(text, _style) => (pos.y, text.clone()),
+                Run::Icon(name) => (pos.y, name.clone()),
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (0.0, "aaa bbb".to_string()),
+                (metrics.line_height, "ccc".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_words_wider_than_a_line() {
+        let metrics = metrics();
+        let layout = TextLayout::new("abcdef", Some(metrics.char_width * 4.0), metrics);
+        assert_eq!(layout.size.y, metrics.line_height * 2.0);
+    }
+
+    #[test]
+    fn line_breaks_start_new_lines() {
+        let metrics = metrics();
+        let layout = TextLayout::new("a\nb", None, metrics);
+        assert_eq!(layout.size.y, metrics.line_height * 2.0);
+        assert_eq!(layout.size.x, metrics.char_width);
+    }
+
+    #[test]
+    fn empty_markup_has_no_size() {
+        let layout = TextLayout::new("", None, metrics());
+        assert!(layout.runs.is_empty());
+        assert_eq!(layout.size, nalgebra_glm::vec2(0.0, 0.0));
+    }
+}
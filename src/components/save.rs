@@ -0,0 +1,281 @@
+//! This module is responsible for the save file, which stores the state of a game between runs. The file is plain text,
+//! one value per line, each line starting with what the value is. A colony's queue, building and station lines come
+//! right after its colony line
+
+use std::fs;
+
+use super::{
+    camera_bookmarks::{CameraBookmark, BOOKMARK_COUNT},
+    construction::{Colony, Project, QueueItem, Ship, STARTING_CREDITS},
+};
+
+/// Where the game is saved to
+pub const SAVE_PATH: &str = "save.txt";
+
+/// The state of a game that is kept between runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct SaveFile {
+    /// The current turn
+    pub turn: usize,
+    /// Index of the star system the camera is in
+    pub current_system: usize,
+    /// The camera bookmarks, indexed by their bookmark action
+    pub bookmarks: [Option<CameraBookmark>; BOOKMARK_COUNT],
+    /// Credits the empire has
+    pub credits: u32,
+    /// Every colony, with its construction queue, buildings and stations
+    pub colonies: Vec<Colony>,
+    /// Every ship
+    pub ships: Vec<Ship>,
+}
+
+impl SaveFile {
+    /// Writes the save file to a path
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+
+    /// Reads a save file from a path
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|err| err.to_string())?;
+        Self::from_text(&text)
+    }
+
+    /// Drops colonies and ships in systems past `system_count`, the number of systems in the galaxy, for saves that are
+    /// stale or edited by hand. Bodies are checked when a colony's system is entered, since that's when they're known
+    pub fn drop_missing_systems(&mut self, system_count: usize) {
+        self.colonies.retain(|colony| colony.system < system_count);
+        self.ships.retain(|ship| ship.system < system_count);
+    }
+
+    /// The contents of the save file
+    fn to_text(&self) -> String {
+        let mut text = format!("turn {}\nsystem {}\n", self.turn, self.current_system);
+        for (i, bookmark) in self.bookmarks.iter().enumerate() {
+            if let Some(bookmark) = bookmark {
+                text += &format!(
+                    "bookmark {} {} {} {} {} {}\n",
+                    i,
+                    bookmark.system,
+                    bookmark.selection,
+                    bookmark.phi,
+                    bookmark.theta,
+                    bookmark.distance
+                );
+            }
+        }
+        text += &format!("credits {}\n", self.credits);
+        for colony in self.colonies.iter() {
+            text += &format!(
+                "colony {} {} {} {} {}\n",
+                colony.system, colony.body, colony.habitability, colony.population, colony.name
+            );
+            for item in colony.queue.items() {
+                text += &format!(
+                    "queue {} {} {}\n",
+                    item.project.id(),
+                    item.target,
+                    item.turns_left
+                );
+            }
+            for building in colony.buildings.iter() {
+                text += &format!("building {}\n", building.id());
+            }
+            for body in colony.stations.iter() {
+                text += &format!("station {}\n", body);
+            }
+        }
+        for ship in self.ships.iter() {
+            text += &format!("ship {} {}\n", ship.system, ship.name);
+        }
+        text
+    }
+
+    /// Parses the contents of a save file
+    fn from_text(text: &str) -> Result<Self, String> {
+        let mut save = Self {
+            turn: 0,
+            current_system: 0,
+            bookmarks: [None; BOOKMARK_COUNT],
+            credits: STARTING_CREDITS,
+            colonies: vec![],
+            ships: vec![],
+        };
+        for (line_number, line) in text.lines().enumerate() {
+            let bad_line = || format!("bad line {} in save file: {:?}", line_number + 1, line);
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                [] => {}
+                ["turn", turn] => save.turn = turn.parse().map_err(|_| bad_line())?,
+                ["system", system] => {
+                    save.current_system = system.parse().map_err(|_| bad_line())?
+                }
+                ["bookmark", i, system, selection, phi, theta, distance] => {
+                    let i: usize = i.parse().map_err(|_| bad_line())?;
+                    if i >= BOOKMARK_COUNT {
+                        return Err(bad_line());
+                    }
+                    save.bookmarks[i] = Some(CameraBookmark {
+                        system: system.parse().map_err(|_| bad_line())?,
+                        selection: selection.parse().map_err(|_| bad_line())?,
+                        phi: phi.parse().map_err(|_| bad_line())?,
+                        theta: theta.parse().map_err(|_| bad_line())?,
+                        distance: distance.parse().map_err(|_| bad_line())?,
+                    });
+                }
+                ["credits", credits] => save.credits = credits.parse().map_err(|_| bad_line())?,
+                ["colony", system, body, habitability, population, name @ ..]
+                    if !name.is_empty() =>
+                {
+                    let mut colony = Colony::new(
+                        system.parse().map_err(|_| bad_line())?,
+                        body.parse().map_err(|_| bad_line())?,
+                        name.join(" "),
+                        habitability.parse().map_err(|_| bad_line())?,
+                    );
+                    colony.population = population.parse().map_err(|_| bad_line())?;
+                    save.colonies.push(colony);
+                }
+                ["queue", project, target, turns_left] => {
+                    let item = QueueItem {
+                        project: Project::from_id(project).ok_or_else(bad_line)?,
+                        target: target.parse().map_err(|_| bad_line())?,
+                        turns_left: turns_left.parse().map_err(|_| bad_line())?,
+                    };
+                    let colony = save.colonies.last_mut().ok_or_else(bad_line)?;
+                    colony.queue.restore(item);
+                }
+                ["building", project] => {
+                    let project = Project::from_id(project).ok_or_else(bad_line)?;
+                    let colony = save.colonies.last_mut().ok_or_else(bad_line)?;
+                    colony.buildings.push(project);
+                }
+                ["station", body] => {
+                    let body = body.parse().map_err(|_| bad_line())?;
+                    let colony = save.colonies.last_mut().ok_or_else(bad_line)?;
+                    colony.stations.push(body);
+                }
+                ["ship", system, name @ ..] if !name.is_empty() => save.ships.push(Ship {
+                    name: name.join(" "),
+                    system: system.parse().map_err(|_| bad_line())?,
+                }),
+                _ => return Err(bad_line()),
+            }
+        }
+        Ok(save)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> SaveFile {
+        let mut bookmarks = [None; BOOKMARK_COUNT];
+        bookmarks[3] = Some(CameraBookmark {
+            system: 4,
+            selection: 2,
+            phi: 0.25,
+            theta: -1.5,
+            distance: 1234.5,
+        });
+
+        let mut credits = 500;
+        let mut home = Colony::new(0, 3, "New Earth".to_string(), 0.87);
+        home.population = 42.123;
+        home.buildings = vec![Project::Mine, Project::Shipyard];
+        home.stations = vec![3, 5, 3];
+        home.enqueue(Project::Station, 5, &mut credits).unwrap();
+        home.enqueue(Project::Frigate, 0, &mut credits).unwrap();
+        home.queue.advance();
+        let outpost = Colony::new(7, 1, "Outpost".to_string(), 0.1);
+
+        SaveFile {
+            turn: 31,
+            current_system: 7,
+            bookmarks,
+            credits,
+            colonies: vec![home, outpost],
+            ships: vec![
+                Ship {
+                    name: "scout 1".to_string(),
+                    system: 0,
+                },
+                Ship {
+                    name: "frigate 2".to_string(),
+                    system: 7,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn round_trips_through_text() {
+        let save = example();
+        assert_eq!(SaveFile::from_text(&save.to_text()), Ok(save));
+    }
+
+    #[test]
+    fn keeps_queue_progress() {
+        let save = SaveFile::from_text(&example().to_text()).unwrap();
+        let queue = save.colonies[0].queue.items();
+        assert_eq!(queue.len(), 2);
+        assert_eq!(queue[0].project, Project::Station);
+        assert_eq!(queue[0].target, 5);
+        assert_eq!(queue[0].turns_left, Project::Station.build_turns() - 1);
+        assert_eq!(queue[1].turns_left, Project::Frigate.build_turns());
+    }
+
+    #[test]
+    fn empty_save_starts_with_starting_credits() {
+        let save = SaveFile::from_text("turn 3\nsystem 1\n").unwrap();
+        assert_eq!(save.credits, STARTING_CREDITS);
+        assert!(save.colonies.is_empty());
+        assert!(save.ships.is_empty());
+    }
+
+    #[test]
+    fn colony_lines_need_a_colony_first() {
+        assert!(SaveFile::from_text("building mine\n").is_err());
+        assert!(SaveFile::from_text("station 2\n").is_err());
+        assert!(SaveFile::from_text("queue mine 0 3\n").is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_projects() {
+        assert!(SaveFile::from_text("colony 0 1 0.5 10 Home\nbuilding castle\n").is_err());
+    }
+
+    #[test]
+    fn drops_colonies_and_ships_outside_the_galaxy() {
+        let mut save = SaveFile::from_text(
+            "colony 0 1 0.5 10 Home\ncolony 99 1 0.5 10 Lost\nship 99 scout 1\nship 3 scout 2\n",
+        )
+        .unwrap();
+        save.drop_missing_systems(10);
+        assert_eq!(save.colonies.len(), 1);
+        assert_eq!(save.colonies[0].name, "Home");
+        assert_eq!(save.ships.len(), 1);
+        assert_eq!(save.ships[0].name, "scout 2");
+    }
+
+    #[test]
+    fn out_of_range_bodies_are_dropped_when_fit_to_the_system() {
+        let mut save = SaveFile::from_text(
+            "colony 0 1 0.5 10 Home\nqueue station 9 2\nqueue mine 1 2\nstation 2\nstation 9\n\
+             colony 0 12 0.5 10 Lost\n",
+        )
+        .unwrap();
+        assert!(save.colonies[0].fit_to_bodies(4));
+        assert_eq!(save.colonies[0].stations, vec![2]);
+        assert_eq!(save.colonies[0].queue.items().len(), 1);
+        assert_eq!(save.colonies[0].queue.items()[0].project, Project::Mine);
+        assert!(!save.colonies[1].fit_to_bodies(4));
+    }
+
+    #[test]
+    fn rejects_colonies_and_ships_without_names() {
+        assert!(SaveFile::from_text("colony 0 1 0.5 10\n").is_err());
+        assert!(SaveFile::from_text("ship 0\n").is_err());
+    }
+}
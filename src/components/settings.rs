@@ -0,0 +1,284 @@
+//! This module is responsible for the settings file, which stores options like the window size and camera sensitivity
+//! between runs. Values that are missing, can't be parsed, or are out of range fall back to their defaults.
+
+use std::fs;
+
+use chrono::NaiveDate;
+
+use super::{
+    calendar::{Calendar, DAYS_PER_YEAR, REAL_SECS_PER_GAME_YEAR},
+    galaxy::GalaxyShape,
+    text_metrics::TextMetrics,
+};
+
+/// Where the settings are saved to
+pub const SETTINGS_PATH: &str = "settings.txt";
+/// How many settings there are
+const SETTING_COUNT: usize = 9;
+
+/// Something the user can change on the options screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    /// Width of the window, in pixels
+    WindowWidth,
+    /// Height of the window, in pixels
+    WindowHeight,
+    /// How many line segments each orbit line is drawn with
+    OrbitSegments,
+    /// Size of the UI font, in points
+    FontSize,
+    /// How far the camera swivels for each pixel the mouse moves, in radians
+    CameraSensitivity,
+    /// How many times bigger the UI is drawn, for high-DPI displays
+    UiScale,
+    /// The shape of the galaxy, as an index into `GalaxyShape::ALL`
+    GalaxyShape,
+    /// The year the game starts on the first of January of
+    StartYear,
+    /// How many turns make a year, which decides how many days pass each turn
+    TurnsPerYear,
+}
+
+impl Setting {
+    /// Every setting, in the order they're shown on the options screen
+    pub const ALL: [Setting; SETTING_COUNT] = [
+        Setting::WindowWidth,
+        Setting::WindowHeight,
+        Setting::OrbitSegments,
+        Setting::FontSize,
+        Setting::CameraSensitivity,
+        Setting::UiScale,
+        Setting::GalaxyShape,
+        Setting::StartYear,
+        Setting::TurnsPerYear,
+    ];
+
+    /// The name of the setting in the settings file
+    pub fn name(&self) -> &'static str {
+        match self {
+            Setting::WindowWidth => "window_width",
+            Setting::WindowHeight => "window_height",
+            Setting::OrbitSegments => "orbit_segments",
+            Setting::FontSize => "font_size",
+            Setting::CameraSensitivity => "camera_sensitivity",
+            Setting::UiScale => "ui_scale",
+            Setting::GalaxyShape => "galaxy_shape",
+            Setting::StartYear => "start_year",
+            Setting::TurnsPerYear => "turns_per_year",
+        }
+    }
+
+    /// Text to show on the options screen for this setting
+    pub fn label(&self) -> &'static str {
+        match self {
+            Setting::WindowWidth => "window width",
+            Setting::WindowHeight => "window height",
+            Setting::OrbitSegments => "orbit segments",
+            Setting::FontSize => "font size",
+            Setting::CameraSensitivity => "camera sensitivity",
+            Setting::UiScale => "ui scale",
+            Setting::GalaxyShape => "galaxy shape",
+            Setting::StartYear => "start year",
+            Setting::TurnsPerYear => "turns per year",
+        }
+    }
+
+    /// The value used when there's no settings file, or the file's value is bad
+    pub fn default_value(&self) -> f32 {
+        match self {
+            Setting::WindowWidth => 800.0,
+            Setting::WindowHeight => 600.0,
+            Setting::OrbitSegments => 1024.0,
+            Setting::FontSize => 16.0,
+            Setting::CameraSensitivity => 0.005,
+            Setting::UiScale => 1.0,
+            Setting::GalaxyShape => 0.0,
+            Setting::StartYear => 2200.0,
+            Setting::TurnsPerYear => REAL_SECS_PER_GAME_YEAR,
+        }
+    }
+
+    /// The smallest and largest values allowed
+    pub fn range(&self) -> (f32, f32) {
+        match self {
+            Setting::WindowWidth => (640.0, 7680.0),
+            Setting::WindowHeight => (480.0, 4320.0),
+            Setting::OrbitSegments => (32.0, 4096.0),
+            Setting::FontSize => (8.0, 48.0),
+            Setting::CameraSensitivity => (0.001, 0.02),
+            Setting::UiScale => (0.5, 3.0),
+            Setting::GalaxyShape => (0.0, (GalaxyShape::ALL.len() - 1) as f32),
+            Setting::StartYear => (1.0, 9999.0),
+            Setting::TurnsPerYear => (1.0, 365.0),
+        }
+    }
+
+    /// Whether changing the setting only takes effect after restarting the game. The font is loaded at startup at
+    /// the font size times the UI scale, and the rest of the UI is laid out to match it, so both need a restart. The
+    /// galaxy and the calendar are made at startup too
+    pub fn needs_restart(&self) -> bool {
+        *self != Setting::CameraSensitivity
+    }
+
+    /// Whether a value is allowed
+    fn is_valid(&self, value: f32) -> bool {
+        let (min, max) = self.range();
+        let whole =
+            matches!(self, Setting::CameraSensitivity | Setting::UiScale) || value.fract() == 0.0;
+        value.is_finite() && min <= value && value <= max && whole
+    }
+
+    /// The next value up or down from a value, as picked on the options screen. Stays within the range
+    pub fn step(&self, value: f32, up: bool) -> f32 {
+        let next = match (self, up) {
+            (Setting::OrbitSegments, true) => value * 2.0,
+            (Setting::OrbitSegments, false) => value / 2.0,
+            (Setting::WindowWidth | Setting::WindowHeight, true) => value + 80.0,
+            (Setting::WindowWidth | Setting::WindowHeight, false) => value - 80.0,
+            (Setting::FontSize, true) => value + 1.0,
+            (Setting::FontSize, false) => value - 1.0,
+            (Setting::CameraSensitivity, true) => value + 0.001,
+            (Setting::CameraSensitivity, false) => value - 0.001,
+            (Setting::UiScale, true) => value + 0.25,
+            (Setting::UiScale, false) => value - 0.25,
+            (Setting::GalaxyShape, true) => value + 1.0,
+            (Setting::GalaxyShape, false) => value - 1.0,
+            (Setting::StartYear | Setting::TurnsPerYear, true) => value + 1.0,
+            (Setting::StartYear | Setting::TurnsPerYear, false) => value - 1.0,
+        };
+        let (min, max) = self.range();
+        next.clamp(min, max)
+    }
+
+    /// Formats a value for the settings file
+    pub fn format(&self, value: f32) -> String {
+        match self {
+            Setting::CameraSensitivity => format!("{:.3}", value),
+            Setting::UiScale => format!("{:.2}", value),
+            _ => format!("{}", value as u32),
+        }
+    }
+
+    /// Formats a value for the options screen. Settings that pick from a list show the name of what's picked
+    pub fn display(&self, value: f32) -> String {
+        match self {
+            Setting::GalaxyShape => GalaxyShape::ALL[value as usize].name().to_string(),
+            _ => self.format(value),
+        }
+    }
+}
+
+/// The value of every setting
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    /// The value of each setting, indexed by the setting
+    values: [f32; SETTING_COUNT],
+}
+
+impl Settings {
+    /// Creates settings with every setting at its default
+    pub fn new() -> Self {
+        Self {
+            values: Setting::ALL.map(|setting| setting.default_value()),
+        }
+    }
+
+    /// Reads the settings file. Settings missing from the file, or with bad values, keep their defaults
+    pub fn load(path: &str) -> Self {
+        let mut settings = Self::new();
+        let Ok(text) = fs::read_to_string(path) else {
+            return settings;
+        };
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            let parsed = line.split_once('=').and_then(|(name, value)| {
+                let setting = Setting::ALL
+                    .into_iter()
+                    .find(|setting| setting.name() == name.trim())?;
+                let value: f32 = value.trim().parse().ok()?;
+                setting.is_valid(value).then_some((setting, value))
+            });
+            match parsed {
+                Some((setting, value)) => settings.set(setting, value),
+                None => println!("ignoring bad line in {}: {:?}", path, line),
+            }
+        }
+        settings
+    }
+
+    /// Writes the settings file
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        let text: String = Setting::ALL
+            .iter()
+            .map(|setting| {
+                format!(
+                    "{} = {}\n",
+                    setting.name(),
+                    setting.format(self.get(*setting))
+                )
+            })
+            .collect();
+        fs::write(path, text)
+    }
+
+    /// The value of a setting
+    pub fn get(&self, setting: Setting) -> f32 {
+        self.values[setting as usize]
+    }
+
+    /// Changes the value of a setting, falling back to its default if the value isn't allowed
+    pub fn set(&mut self, setting: Setting, value: f32) {
+        self.values[setting as usize] = if setting.is_valid(value) {
+            value
+        } else {
+            setting.default_value()
+        };
+    }
+
+    /// The size of the window, in pixels
+    pub fn window_size(&self) -> nalgebra_glm::I32Vec2 {
+        nalgebra_glm::I32Vec2::new(
+            self.get(Setting::WindowWidth) as i32,
+            self.get(Setting::WindowHeight) as i32,
+        )
+    }
+
+    /// How many line segments each orbit line is drawn with
+    pub fn orbit_segments(&self) -> usize {
+        self.get(Setting::OrbitSegments) as usize
+    }
+
+    /// Size of the UI font, in points
+    pub fn font_size(&self) -> u16 {
+        self.get(Setting::FontSize) as u16
+    }
+
+    /// How far the camera swivels for each pixel the mouse moves, in radians
+    pub fn camera_sensitivity(&self) -> f32 {
+        self.get(Setting::CameraSensitivity)
+    }
+
+    /// How many times bigger the UI is drawn, for high-DPI displays
+    pub fn ui_scale(&self) -> f32 {
+        self.get(Setting::UiScale)
+    }
+
+    /// The shape of the galaxy
+    pub fn galaxy_shape(&self) -> GalaxyShape {
+        GalaxyShape::ALL[self.get(Setting::GalaxyShape) as usize]
+    }
+
+    /// The calendar, starting on the first of January of the start year, with each turn taking an even share of a
+    /// year
+    pub fn calendar(&self) -> Calendar {
+        let start_year = self.get(Setting::StartYear) as i32;
+        Calendar::new(
+            NaiveDate::from_ymd_opt(start_year, 1, 1).unwrap(),
+            DAYS_PER_YEAR / self.get(Setting::TurnsPerYear),
+        )
+    }
+
+    /// The size of the UI font's characters, for the font size and UI scale
+    pub fn text_metrics(&self) -> TextMetrics {
+        TextMetrics::new(self.font_size(), self.ui_scale())
+    }
+}
@@ -0,0 +1,111 @@
+//! This module is responsible for defining the star component, and for feeding stars to the 3d shader as lights.
+//! Shadows are not rendered from a shadow map. Instead, every non-star body is uploaded as a sphere, and the 3d
+//! shader checks whether any of them sit between a fragment and each star. This lets moons eclipse planets correctly
+//! anywhere on their orbits, no matter which star they block.
+
+use std::ffi::CStr;
+
+use apricot::render_core::ModelComponent;
+use hecs::World;
+
+use super::planet::Planet;
+
+/// The most stars the 3d shader can be lit by at once. Must match `MAX_STARS` in `3d.frag`
+pub const MAX_STARS: usize = 4;
+/// The most bodies that can cast shadows at once. Must match `MAX_OCCLUDERS` in `3d.frag`. Past this, the smallest
+/// bodies stop casting shadows
+pub const MAX_OCCLUDERS: usize = 32;
+
+/// Marks a planetary body as a star that emits light
+pub struct Star {
+    /// How bright the star is, relative to the sun
+    pub luminosity: f32,
+    /// The color of the light the star emits
+    pub color: nalgebra_glm::Vec3,
+}
+
+impl Star {
+    /// Creates a new star
+    pub fn new(luminosity: f32, color: nalgebra_glm::Vec3) -> Self {
+        Self { luminosity, color }
+    }
+}
+
+/// Uploads the position, color and size of every star to the 3d shader, so that fragments can be lit by each of them,
+/// along with the position and size of every other body, so that they can cast shadows
+pub fn star_light_system(world: &World, program_id: u32) {
+    let mut positions = Vec::with_capacity(MAX_STARS);
+    let mut colors = Vec::with_capacity(MAX_STARS);
+    let mut radii = Vec::with_capacity(MAX_STARS);
+    for (_entity, (model, planet, star)) in
+        world.query::<(&ModelComponent, &Planet, &Star)>().iter()
+    {
+        if positions.len() >= MAX_STARS {
+            break;
+        }
+        positions.push(model.get_position());
+        colors.push(star.color * star.luminosity);
+        radii.push(planet.body_radius);
+    }
+
+    // Stars are already uploaded as lights, they don't block each other
+    let mut occluders: Vec<(nalgebra_glm::Vec3, f32)> = world
+        .query::<(&ModelComponent, &Planet, Option<&Star>)>()
+        .iter()
+        .filter(|(_entity, (_model, _planet, star))| star.is_none())
+        .map(|(_entity, (model, planet, _star))| (model.get_position(), planet.body_radius))
+        .collect();
+    // The biggest bodies cast the most noticeable shadows, so they're kept when there are too many
+    occluders.sort_by(|a, b| b.1.total_cmp(&a.1));
+    occluders.truncate(MAX_OCCLUDERS);
+    let occluder_positions: Vec<nalgebra_glm::Vec3> = occluders
+        .iter()
+        .map(|(position, _radius)| *position)
+        .collect();
+    let occluder_radii: Vec<f32> = occluders
+        .iter()
+        .map(|(_position, radius)| *radius)
+        .collect();
+
+    unsafe {
+        gl::UseProgram(program_id);
+        gl::Uniform1i(
+            uniform_location(program_id, c"u_star_count"),
+            positions.len() as i32,
+        );
+        gl::Uniform3fv(
+            uniform_location(program_id, c"u_star_positions"),
+            positions.len() as i32,
+            positions.as_ptr() as *const f32,
+        );
+        gl::Uniform3fv(
+            uniform_location(program_id, c"u_star_colors"),
+            colors.len() as i32,
+            colors.as_ptr() as *const f32,
+        );
+        gl::Uniform1fv(
+            uniform_location(program_id, c"u_star_radii"),
+            radii.len() as i32,
+            radii.as_ptr(),
+        );
+        gl::Uniform1i(
+            uniform_location(program_id, c"u_occluder_count"),
+            occluder_positions.len() as i32,
+        );
+        gl::Uniform3fv(
+            uniform_location(program_id, c"u_occluder_positions"),
+            occluder_positions.len() as i32,
+            occluder_positions.as_ptr() as *const f32,
+        );
+        gl::Uniform1fv(
+            uniform_location(program_id, c"u_occluder_radii"),
+            occluder_radii.len() as i32,
+            occluder_radii.as_ptr(),
+        );
+    }
+}
+
+/// Looks up the location of a uniform in a shader program
+pub(crate) fn uniform_location(program_id: u32, name: &CStr) -> i32 {
+    unsafe { gl::GetUniformLocation(program_id, name.as_ptr()) }
+}
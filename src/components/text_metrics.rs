@@ -0,0 +1,52 @@
+//! This module is responsible for the size of the UI font's characters, which the panels lay their text out with
+
+/// How tall each line of text is, for each point of font size. Consolas at 16pt has 20 pixel lines
+const LINE_HEIGHT_PER_POINT: f32 = 1.25;
+/// How wide each character is, for each point of font size. Consolas characters are 0.55 em wide, so 9 pixels at 16pt
+const CHAR_WIDTH_PER_POINT: f32 = 0.55;
+
+/// The size of the UI font's characters in pixels. The font is only loaded at startup, so these stay the same until
+/// the game restarts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextMetrics {
+    /// How many times bigger the UI is drawn
+    pub ui_scale: f32,
+    /// How tall each line of text is, in pixels
+    pub line_height: f32,
+    /// How wide each character is, in pixels. Used to figure out which line is being clicked
+    pub char_width: f32,
+}
+
+impl TextMetrics {
+    /// The metrics of the font at a size in points, drawn at a UI scale
+    pub fn new(font_size: u16, ui_scale: f32) -> Self {
+        let points = Self::points(font_size, ui_scale) as f32;
+        Self {
+            ui_scale,
+            line_height: (points * LINE_HEIGHT_PER_POINT).round(),
+            char_width: (points * CHAR_WIDTH_PER_POINT).round(),
+        }
+    }
+
+    /// The size the font is loaded at, in points
+    pub fn points(font_size: u16, ui_scale: f32) -> u16 {
+        (font_size as f32 * ui_scale).round() as u16
+    }
+
+    /// Scales a size given in pixels at a UI scale of 1, like a panel's padding, to the UI scale
+    pub fn scale(&self, pixels: f32) -> f32 {
+        pixels * self.ui_scale
+    }
+
+    /// How wide plain text is, in pixels. The font is monospaced, so this is just the number of characters
+    pub fn text_width(&self, text: &str) -> f32 {
+        self.char_width * text.chars().count() as f32
+    }
+}
+
+impl Default for TextMetrics {
+    /// The metrics of the default 16pt font at the default UI scale
+    fn default() -> Self {
+        Self::new(16, 1.0)
+    }
+}
@@ -0,0 +1,99 @@
+//! This module is responsible for defining how fast the game runs: either turn-based, where turns only happen when the
+//! next-turn button is pressed, or real-time, where turns happen automatically at a chosen speed
+
+use super::input::{Action, InputMap};
+
+/// How long a turn takes to play out at normal speed, in seconds
+const BASE_TURN_DURATION: f32 = 1.0;
+/// The action for each game speed
+const SPEED_ACTIONS: [(Action, GameSpeed); 4] = [
+    (Action::TurnBased, GameSpeed::TurnBased),
+    (Action::SpeedNormal, GameSpeed::Normal),
+    (Action::SpeedDouble, GameSpeed::Double),
+    (Action::SpeedQuintuple, GameSpeed::Quintuple),
+];
+
+/// How fast turns happen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameSpeed {
+    /// Turns only happen when the next-turn button is pressed
+    TurnBased,
+    /// Real-time mode, but no turns happen
+    Paused,
+    /// One turn a second
+    Normal,
+    /// Two turns a second
+    Double,
+    /// Five turns a second
+    Quintuple,
+}
+
+impl GameSpeed {
+    /// How many times faster than normal turns happen, or None if turns don't happen automatically
+    fn multiplier(&self) -> Option<f32> {
+        match self {
+            GameSpeed::TurnBased | GameSpeed::Paused => None,
+            GameSpeed::Normal => Some(1.0),
+            GameSpeed::Double => Some(2.0),
+            GameSpeed::Quintuple => Some(5.0),
+        }
+    }
+
+    /// Text to show in the HUD for this speed
+    pub fn label(&self) -> &'static str {
+        match self {
+            GameSpeed::TurnBased => "turn-based",
+            GameSpeed::Paused => "paused",
+            GameSpeed::Normal => "1x",
+            GameSpeed::Double => "2x",
+            GameSpeed::Quintuple => "5x",
+        }
+    }
+}
+
+/// Keeps track of the game speed, and lets the user change it with the speed actions
+pub struct TimeControl {
+    /// The current game speed
+    pub speed: GameSpeed,
+    /// The real-time speed to go back to when unpausing
+    unpaused_speed: GameSpeed,
+}
+
+impl TimeControl {
+    /// Creates a new time control, starting turn-based
+    pub fn new() -> Self {
+        Self {
+            speed: GameSpeed::TurnBased,
+            unpaused_speed: GameSpeed::Normal,
+        }
+    }
+
+    /// Changes the game speed based on the speed actions. Each speed action picks a speed, and the pause action pauses
+    /// and unpauses
+    pub fn update(&mut self, input: &InputMap) {
+        for (action, speed) in SPEED_ACTIONS {
+            if input.pressed(action) {
+                self.speed = speed;
+            }
+        }
+        if input.pressed(Action::Pause) {
+            self.speed = match self.speed {
+                GameSpeed::Paused => self.unpaused_speed,
+                _ => GameSpeed::Paused,
+            };
+        }
+        if self.speed.multiplier().is_some() {
+            self.unpaused_speed = self.speed;
+        }
+    }
+
+    /// Whether turns should happen on their own
+    pub fn is_running(&self) -> bool {
+        self.speed.multiplier().is_some()
+    }
+
+    /// How long a turn takes to play out at the current speed, in seconds
+    pub fn turn_duration(&self) -> f32 {
+        BASE_TURN_DURATION / self.speed.multiplier().unwrap_or(1.0)
+    }
+}
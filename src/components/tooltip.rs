@@ -0,0 +1,142 @@
+//! This module is responsible for tooltips, which explain whatever the mouse has been resting on, like a button or a
+//! body, in a small panel next to the mouse
+
+use apricot::{app::App, rectangle::Rectangle};
+use hecs::{Entity, World};
+
+use super::{
+    anchor::window_size, construction::Colony, info_panel::body_stats, label::set_opacity,
+    planet::Planet, rich_text::TextLayout, text_metrics::TextMetrics,
+};
+
+/// How long the mouse has to rest on something before its tooltip is shown, in seconds
+const TOOLTIP_DELAY: f32 = 0.5;
+/// How far from the mouse the tooltip is drawn, in pixels at a UI scale of 1
+const MOUSE_OFFSET: f32 = 16.0;
+/// Space between the edge of the tooltip's background and its text, in pixels at a UI scale of 1
+const PADDING: f32 = 6.0;
+/// How opaque the tooltip's background is
+const BACKGROUND_OPACITY: f32 = 0.85;
+/// How wide the text of a tooltip can get before it wraps, in pixels at a UI scale of 1
+const MAX_TEXT_WIDTH: f32 = 300.0;
+
+/// What a tooltip is attached to. The delay restarts whenever the mouse moves onto something else
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TooltipTarget {
+    /// A button or other widget, by its id
+    Widget(&'static str),
+    /// A body in the 3d view
+    Body(Entity),
+    /// A star system on the galaxy map, by its index
+    System(usize),
+}
+
+/// Shows a tooltip for whatever the mouse is resting on, once it has rested there long enough
+pub struct Tooltip {
+    /// What the mouse is over, if anything with a tooltip
+    target: Option<TooltipTarget>,
+    /// When the mouse moved onto the target, in seconds
+    hover_start: f32,
+    /// The text of the tooltip, laid out again every update so that it stays current
+    text: TextLayout,
+    /// The size of the font the text is laid out for
+    metrics: TextMetrics,
+}
+
+impl Tooltip {
+    /// Creates a new tooltip, with nothing hovered
+    pub fn new(metrics: TextMetrics) -> Self {
+        Self {
+            target: None,
+            hover_start: 0.0,
+            text: TextLayout::new("", None, metrics),
+            metrics,
+        }
+    }
+
+    /// Changes what the mouse is over, along with its tooltip text as rich text markup. Should be called once a tick
+    pub fn update(&mut self, app: &App, hovered: Option<(TooltipTarget, String)>) {
+        let (target, markup) = hovered.unzip();
+        if target != self.target {
+            self.target = target;
+            self.hover_start = app.seconds;
+        }
+        self.text = TextLayout::new(
+            &markup.unwrap_or_default(),
+            Some(self.metrics.scale(MAX_TEXT_WIDTH)),
+            self.metrics,
+        );
+    }
+
+    /// Whether the tooltip is shown
+    pub fn is_shown(&self, app: &App) -> bool {
+        self.target.is_some()
+            && !self.text.runs.is_empty()
+            && app.seconds - self.hover_start >= TOOLTIP_DELAY
+    }
+
+    /// Renders the tooltip next to the mouse, on a background panel. The tooltip flips to the other side of the mouse
+    /// when it would go off the edge of the window
+    pub fn render(&self, app: &App, program_2d_id: u32) {
+        if !self.is_shown(app) {
+            return;
+        }
+
+        let padding = self.metrics.scale(PADDING);
+        let mouse_offset = self.metrics.scale(MOUSE_OFFSET);
+        let size = self.text.size + nalgebra_glm::vec2(padding * 2.0, padding * 2.0);
+        let window_size = window_size(app);
+        let mut pos = app.mouse_pos + nalgebra_glm::vec2(mouse_offset, mouse_offset);
+        if pos.x + size.x > window_size.x {
+            pos.x = app.mouse_pos.x - mouse_offset - size.x;
+        }
+        if pos.y + size.y > window_size.y {
+            pos.y = app.mouse_pos.y - mouse_offset - size.y;
+        }
+        let pos = nalgebra_glm::max2(&pos, &nalgebra_glm::vec2(0.0, 0.0));
+
+        set_opacity(program_2d_id, BACKGROUND_OPACITY);
+        app.renderer.copy_texture(
+            Rectangle::new(pos.x, pos.y, size.x, size.y),
+            app.renderer.get_texture_id_from_name("panel").unwrap(),
+            Rectangle::new(0.0, 0.0, 8.0, 8.0),
+        );
+
+        set_opacity(program_2d_id, 1.0);
+        self.text.draw(
+            app,
+            program_2d_id,
+            pos + nalgebra_glm::vec2(padding, padding),
+        );
+    }
+}
+
+/// Builds the tooltip text for a body as rich text markup, summarizing its stats and its colony's, if it has one. The
+/// parts of stats are left for the info panel
+pub fn body_tooltip(world: &World, entity: Entity, colony: Option<&Colony>) -> String {
+    let Ok(planet) = world.get::<&Planet>(entity) else {
+        return String::new();
+    };
+
+    let mut lines = vec![format!("[b]{}[/b]", planet.name)];
+    for stat in body_stats(world, entity, colony) {
+        if stat.depth > 0 {
+            continue;
+        }
+        let line = match stat.rating {
+            Some(rating) => {
+                let color = if rating >= 0.5 {
+                    "green"
+                } else if rating >= 0.1 {
+                    "yellow"
+                } else {
+                    "red"
+                };
+                format!("{}: [color={}]{}[/color]", stat.name, color, stat.value)
+            }
+            None => stat.text(),
+        };
+        lines.push(line);
+    }
+    lines.join("\n")
+}
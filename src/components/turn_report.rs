@@ -0,0 +1,464 @@
+//! This module is responsible for the end-of-turn report, which sums up what changed during a turn: the empire's stats
+//! compared to the turn before, and everything that happened. The report is written as rich text, so the same report
+//! can be shown in a panel or exported as plain text.
+
+use std::fs;
+
+use apricot::{app::App, rectangle::Rectangle};
+
+use super::{
+    label::set_opacity,
+    notifications::{Notification, NotificationKind},
+    rich_text::{plain_text, TextLayout},
+    text_metrics::TextMetrics,
+};
+
+/// Where reports are exported to. The turn number is added to the end
+const REPORT_PATH_PREFIX: &str = "turn-report-";
+/// Command line flag that exports every turn's report as it's made, for runs where nobody reads the panel
+pub const EXPORT_REPORTS_FLAG: &str = "--export-reports";
+/// Space between the edge of the panel's background and its text, in pixels at a UI scale of 1
+const PADDING: f32 = 10.0;
+/// How opaque the panel's background is
+const BACKGROUND_OPACITY: f32 = 0.9;
+/// The clickable lines at the bottom of the panel
+const ACTIONS: [&str; 2] = ["export as text", "close"];
+/// How much of the window's height the panel can take up. Longer reports scroll
+const MAX_HEIGHT_FRACTION: f32 = 0.8;
+/// Shown above the actions while there's more of the report below
+const SCROLL_HINT: &str = "(scroll for more)";
+
+/// A number tracked from turn to turn, like how many systems have been explored
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stat {
+    pub name: &'static str,
+    pub value: f32,
+}
+
+/// The empire's stats at the end of a turn
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TurnSnapshot {
+    pub stats: Vec<Stat>,
+}
+
+/// Everything that changed during a turn
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurnReport {
+    pub turn: usize,
+    /// The in-game date the turn ended on
+    pub date: String,
+    /// Each stat's value, and how much it changed since the turn before
+    pub stats: Vec<(Stat, f32)>,
+    /// What happened during the turn
+    pub events: Vec<Notification>,
+}
+
+impl TurnReport {
+    /// Builds the report for a turn. Stats missing from the previous snapshot are counted as changing from zero
+    pub fn new(
+        turn: usize,
+        date: String,
+        current: &TurnSnapshot,
+        previous: &TurnSnapshot,
+        events: Vec<Notification>,
+    ) -> Self {
+        let stats = current
+            .stats
+            .iter()
+            .map(|stat| {
+                let before = previous
+                    .stats
+                    .iter()
+                    .find(|previous_stat| previous_stat.name == stat.name)
+                    .map_or(0.0, |previous_stat| previous_stat.value);
+                (*stat, stat.value - before)
+            })
+            .collect();
+        let events = events
+            .into_iter()
+            .filter(|event| event.kind != NotificationKind::TurnStarted)
+            .collect();
+        Self {
+            turn,
+            date,
+            stats,
+            events,
+        }
+    }
+
+    /// The report as rich text markup
+    pub fn to_markup(&self) -> String {
+        let mut lines = vec![format!("[b]turn {} report - {}[/b]", self.turn, self.date)];
+        lines.push(String::new());
+        lines.push("[b]empire[/b]".to_string());
+        for (stat, change) in self.stats.iter() {
+            let change = round_amount(*change);
+            let change = if change > 0.0 {
+                format!("[color=green]+{}[/color]", format_amount(change))
+            } else if change < 0.0 {
+                format!("[color=red]{}[/color]", format_amount(change))
+            } else {
+                "[color=grey]no change[/color]".to_string()
+            };
+            lines.push(format!(
+                "- {}: {} ({})",
+                stat.name,
+                format_amount(stat.value),
+                change
+            ));
+        }
+        lines.push(String::new());
+        lines.push("[b]events[/b]".to_string());
+        if self.events.is_empty() {
+            lines.push("[color=grey]nothing happened[/color]".to_string());
+        }
+        for event in self.events.iter() {
+            lines.push(format!("- {}", event.text.replace('[', "[[")));
+        }
+        lines.join("\n")
+    }
+
+    /// The report as plain text, the same as it reads in the panel
+    pub fn to_text(&self) -> String {
+        plain_text(&self.to_markup())
+    }
+
+    /// Writes the report to a text file, named after the turn
+    pub fn export(&self) -> std::io::Result<String> {
+        let path = format!("{}{}.txt", REPORT_PATH_PREFIX, self.turn);
+        fs::write(&path, self.to_text() + "\n")?;
+        Ok(path)
+    }
+}
+
+/// Rounds a stat or change to the one decimal place it's shown with
+fn round_amount(value: f32) -> f32 {
+    (value * 10.0).round() / 10.0
+}
+
+/// Formats a stat or change with one decimal place, leaving it off whole numbers
+fn format_amount(value: f32) -> String {
+    let value = round_amount(value);
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        format!("{:.1}", value)
+    }
+}
+
+/// What the player picked on the report panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportAction {
+    Export,
+    Close,
+}
+
+/// Panel in the middle of the screen that shows the latest turn report
+pub struct TurnReportPanel {
+    /// The center of the window, which the panel is centered on
+    center: nalgebra_glm::Vec2,
+    /// Whether the panel is shown
+    pub open: bool,
+    /// The latest report, and its text laid out for drawing
+    report: Option<(TurnReport, TextLayout)>,
+    /// How far down the report's text is scrolled, in pixels
+    scroll: f32,
+    /// The size of the font the panel's text is laid out with
+    metrics: TextMetrics,
+}
+
+impl TurnReportPanel {
+    /// Creates a new, closed panel with no report
+    pub fn new(center: nalgebra_glm::Vec2, metrics: TextMetrics) -> Self {
+        Self {
+            center,
+            open: false,
+            report: None,
+            scroll: 0.0,
+            metrics,
+        }
+    }
+
+    /// Moves the panel, for when the window is resized
+    pub fn set_center(&mut self, center: nalgebra_glm::Vec2) {
+        self.center = center;
+    }
+
+    /// Replaces the report shown in the panel
+    pub fn set_report(&mut self, report: TurnReport) {
+        let text = TextLayout::new(&report.to_markup(), None, self.metrics);
+        self.report = Some((report, text));
+        self.scroll = 0.0;
+    }
+
+    /// The latest report, if a turn has been played
+    pub fn report(&self) -> Option<&TurnReport> {
+        self.report.as_ref().map(|(report, _text)| report)
+    }
+
+    /// Scrolls the report with the mouse wheel, and checks if one of the actions at the bottom of the panel is
+    /// clicked. Closing is handled here
+    pub fn update(&mut self, app: &App) -> Option<ReportAction> {
+        let pos = self.top_left();
+        let size = self.size();
+        if Rectangle::new(pos.x, pos.y, size.x, size.y).contains_point(&app.mouse_pos) {
+            self.scroll = (self.scroll - self.metrics.line_height * app.mouse_wheel as f32)
+                .clamp(0.0, self.max_scroll());
+        }
+
+        if !app.mouse_left_clicked {
+            return None;
+        }
+        let action = match (0..ACTIONS.len())
+            .find(|i| self.action_rect(*i).contains_point(&app.mouse_pos))?
+        {
+            0 => ReportAction::Export,
+            _ => ReportAction::Close,
+        };
+        if action == ReportAction::Close {
+            self.open = false;
+        }
+        Some(action)
+    }
+
+    /// Renders the panel to the screen, on a background
+    pub fn render(&self, app: &App, program_2d_id: u32) {
+        let Some((_report, text)) = &self.report else {
+            return;
+        };
+        let pos = self.top_left();
+        let size = self.size();
+
+        set_opacity(program_2d_id, BACKGROUND_OPACITY);
+        app.renderer.copy_texture(
+            Rectangle::new(pos.x, pos.y, size.x, size.y),
+            app.renderer.get_texture_id_from_name("panel").unwrap(),
+            Rectangle::new(0.0, 0.0, 8.0, 8.0),
+        );
+        set_opacity(program_2d_id, 1.0);
+
+        let padding = self.metrics.scale(PADDING);
+        text.draw_window(
+            app,
+            program_2d_id,
+            pos + nalgebra_glm::vec2(padding, padding),
+            self.scroll,
+            self.text_height(),
+        );
+        // The blank line above the actions says when there's more to scroll to
+        if self.scroll < self.max_scroll() {
+            app.renderer.draw_text(
+                self.action_pos(0) - nalgebra_glm::vec2(0.0, self.metrics.line_height),
+                SCROLL_HINT,
+            );
+        }
+        for (i, action) in ACTIONS.iter().enumerate() {
+            let marker = if self.action_rect(i).contains_point(&app.mouse_pos) {
+                ">"
+            } else {
+                " "
+            };
+            app.renderer.draw_text(
+                self.action_pos(i),
+                format!("{} {}", marker, action).as_str(),
+            );
+        }
+    }
+
+    /// The height of the report's text, in pixels
+    fn full_text_height(&self) -> f32 {
+        self.report
+            .as_ref()
+            .map_or(0.0, |(_report, text)| text.size.y)
+    }
+
+    /// How much of the report's text fits in the panel, in pixels. The panel is capped to part of the window's
+    /// height, and only shows whole lines
+    fn text_height(&self) -> f32 {
+        let reserved = self.metrics.line_height * (ACTIONS.len() + 1) as f32
+            + self.metrics.scale(PADDING) * 2.0;
+        let max_height = self.center.y * 2.0 * MAX_HEIGHT_FRACTION - reserved;
+        let max_lines = (max_height / self.metrics.line_height).floor().max(1.0);
+        self.full_text_height()
+            .min(max_lines * self.metrics.line_height)
+    }
+
+    /// How far down the report's text can be scrolled, in pixels
+    fn max_scroll(&self) -> f32 {
+        self.full_text_height() - self.text_height()
+    }
+
+    /// The size of the panel, including its background
+    fn size(&self) -> nalgebra_glm::Vec2 {
+        let text_size = self
+            .report
+            .as_ref()
+            .map_or(nalgebra_glm::vec2(0.0, 0.0), |(_report, text)| {
+                nalgebra_glm::vec2(text.size.x, self.text_height())
+            });
+        let actions_width = ACTIONS
+            .iter()
+            .map(|action| action.len() + 2)
+            .chain([SCROLL_HINT.len()])
+            .map(|len| self.metrics.char_width * len as f32)
+            .fold(0.0, f32::max);
+        let padding = self.metrics.scale(PADDING);
+        nalgebra_glm::vec2(
+            text_size.x.max(actions_width) + padding * 2.0,
+            text_size.y + self.metrics.line_height * (ACTIONS.len() + 1) as f32 + padding * 2.0,
+        )
+    }
+
+    /// The top-left corner of the panel
+    fn top_left(&self) -> nalgebra_glm::Vec2 {
+        self.center - self.size() / 2.0
+    }
+
+    /// The top-left corner of the `i`th action's line, below the report with a blank line between
+    fn action_pos(&self, i: usize) -> nalgebra_glm::Vec2 {
+        let pos = self.top_left();
+        let size = self.size();
+        let padding = self.metrics.scale(PADDING);
+        nalgebra_glm::vec2(
+            pos.x + padding,
+            pos.y + size.y - padding - self.metrics.line_height * (ACTIONS.len() - i) as f32,
+        )
+    }
+
+    /// The rectangle that the `i`th action's line covers on the screen
+    fn action_rect(&self, i: usize) -> Rectangle {
+        let pos = self.action_pos(i);
+        Rectangle::new(
+            pos.x,
+            pos.y,
+            self.metrics.char_width * (ACTIONS[i].len() + 2) as f32,
+            self.metrics.line_height,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(stats: &[(&'static str, f32)]) -> TurnSnapshot {
+        TurnSnapshot {
+            stats: stats
+                .iter()
+                .map(|(name, value)| Stat {
+                    name,
+                    value: *value,
+                })
+                .collect(),
+        }
+    }
+
+    fn notification(kind: NotificationKind, text: &str) -> Notification {
+        Notification {
+            turn: 3,
+            kind,
+            text: text.to_string(),
+            target: None,
+        }
+    }
+
+    fn report(
+        current: &TurnSnapshot,
+        previous: &TurnSnapshot,
+        events: Vec<Notification>,
+    ) -> TurnReport {
+        TurnReport::new(3, "1 March 2200".to_string(), current, previous, events)
+    }
+
+    #[test]
+    fn stats_change_from_the_previous_snapshot() {
+        let report = report(
+            &snapshot(&[("credits", 150.0), ("colonies", 2.0)]),
+            &snapshot(&[("colonies", 2.0), ("credits", 100.0)]),
+            vec![],
+        );
+        assert_eq!(report.stats[0].1, 50.0);
+        assert_eq!(report.stats[1].1, 0.0);
+    }
+
+    #[test]
+    fn missing_stats_change_from_zero() {
+        let report = report(
+            &snapshot(&[("ships", 3.0)]),
+            &TurnSnapshot::default(),
+            vec![],
+        );
+        assert_eq!(report.stats[0].1, 3.0);
+    }
+
+    #[test]
+    fn turn_started_events_are_left_out() {
+        let report = report(
+            &TurnSnapshot::default(),
+            &TurnSnapshot::default(),
+            vec![
+                notification(NotificationKind::TurnStarted, "turn 3 started"),
+                notification(NotificationKind::GameSaved, "saved"),
+            ],
+        );
+        assert_eq!(report.events.len(), 1);
+        assert_eq!(report.events[0].kind, NotificationKind::GameSaved);
+    }
+
+    #[test]
+    fn text_lists_stats_and_events() {
+        let report = report(
+            &snapshot(&[("credits", 150.0), ("colonies", 2.0), ("ships", 1.0)]),
+            &snapshot(&[("credits", 100.0), ("colonies", 2.0), ("ships", 2.0)]),
+            vec![notification(NotificationKind::Milestone, "a milestone")],
+        );
+        assert_eq!(
+            report.to_text(),
+            "turn 3 report - 1 March 2200\n\
+             \n\
+             empire\n\
+             - credits: 150 (+50)\n\
+             - colonies: 2 (no change)\n\
+             - ships: 1 (-1)\n\
+             \n\
+             events\n\
+             - a milestone"
+        );
+    }
+
+    #[test]
+    fn quiet_turns_say_nothing_happened() {
+        let report = report(&TurnSnapshot::default(), &TurnSnapshot::default(), vec![]);
+        assert!(report.to_text().ends_with("events\nnothing happened"));
+    }
+
+    #[test]
+    fn event_text_is_not_read_as_markup() {
+        let report = report(
+            &TurnSnapshot::default(),
+            &TurnSnapshot::default(),
+            vec![notification(
+                NotificationKind::GameSaved,
+                "saved [b]slot[/b] [1]",
+            )],
+        );
+        assert!(report.to_markup().contains("saved [[b]slot[[/b] [[1]"));
+        assert!(report.to_text().ends_with("- saved [b]slot[/b] [1]"));
+    }
+
+    #[test]
+    fn changes_are_shown_to_one_decimal_place() {
+        let report = report(
+            &snapshot(&[("population (millions)", 10.3), ("credits", 100.0)]),
+            &snapshot(&[("population (millions)", 10.0), ("credits", 100.02)]),
+            vec![],
+        );
+        let text = report.to_text();
+        assert!(
+            text.contains("- population (millions): 10.3 (+0.3)"),
+            "{}",
+            text
+        );
+        assert!(text.contains("- credits: 100 (no change)"), "{}", text);
+    }
+}
@@ -0,0 +1,278 @@
+//! This module is responsible for tweens, which animate a value from one point to another over time along an easing
+//! curve. Tweens don't keep their own clock, the time is passed in, usually `app.seconds`.
+
+/// The shape of a tween's animation curve
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    /// Constant speed
+    Linear,
+    /// Starts fast, slows down
+    QuadOut,
+    /// Starts fast, slows down, more sharply than `QuadOut`
+    CubicOut,
+    /// Starts slow, speeds up, slows down, following a sine wave
+    SineInOut,
+    /// Overshoots the end a little, then settles back
+    BackOut,
+}
+
+impl Easing {
+    /// Maps how far through the animation it is, from 0 to 1, to how far the value has moved
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::QuadOut => 1.0 - (1.0 - t).powi(2),
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::SineInOut => -((std::f32::consts::PI * t).cos() - 1.0) / 2.0,
+            Easing::BackOut => {
+                const OVERSHOOT: f32 = 1.70158;
+                1.0 + (OVERSHOOT + 1.0) * (t - 1.0).powi(3) + OVERSHOOT * (t - 1.0).powi(2)
+            }
+        }
+    }
+}
+
+/// Values that can be animated by a tween
+pub trait Tweenable: Copy {
+    /// The value `t` of the way from `self` to `other`
+    fn interpolate(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Tweenable for f32 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Tweenable for nalgebra_glm::Vec2 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        nalgebra_glm::lerp(self, other, t)
+    }
+}
+
+impl Tweenable for nalgebra_glm::Vec3 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        nalgebra_glm::lerp(self, other, t)
+    }
+}
+
+/// Colors are RGBA
+impl Tweenable for nalgebra_glm::Vec4 {
+    fn interpolate(&self, other: &Self, t: f32) -> Self {
+        nalgebra_glm::lerp(self, other, t)
+    }
+}
+
+/// One leg of a tween, moving to a value
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Step<T> {
+    to: T,
+    /// How long the step takes, in seconds
+    duration: f32,
+    easing: Easing,
+}
+
+/// Animates a value through a chain of steps, one after another
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tween<T: Tweenable> {
+    /// The value before the first step
+    from: T,
+    /// When the first step starts, in seconds
+    start_time: f32,
+    steps: Vec<Step<T>>,
+}
+
+impl<T: Tweenable> Tween<T> {
+    /// Creates a tween from one value to another, starting at `start_time` and taking `duration` seconds
+    pub fn new(from: T, to: T, start_time: f32, duration: f32, easing: Easing) -> Self {
+        Self::fixed(from)
+            .then(to, duration, easing)
+            .starting_at(start_time)
+    }
+
+    /// Creates a tween that stays at a value, and is already finished
+    pub fn fixed(value: T) -> Self {
+        Self {
+            from: value,
+            start_time: 0.0,
+            steps: vec![],
+        }
+    }
+
+    /// Chains another step on after the others, moving to `to` over `duration` seconds
+    pub fn then(mut self, to: T, duration: f32, easing: Easing) -> Self {
+        self.steps.push(Step {
+            to,
+            duration,
+            easing,
+        });
+        self
+    }
+
+    /// Changes when the first step starts
+    pub fn starting_at(mut self, start_time: f32) -> Self {
+        self.start_time = start_time;
+        self
+    }
+
+    /// The value at a time
+    pub fn value(&self, now: f32) -> T {
+        let mut from = self.from;
+        let mut elapsed = now - self.start_time;
+        for step in self.steps.iter() {
+            if elapsed < step.duration {
+                return from.interpolate(&step.to, step.easing.apply(elapsed / step.duration));
+            }
+            elapsed -= step.duration;
+            from = step.to;
+        }
+        from
+    }
+
+    /// The value once every step is done
+    pub fn end_value(&self) -> T {
+        self.steps.last().map_or(self.from, |step| step.to)
+    }
+
+    /// When the last step is done, in seconds
+    pub fn end_time(&self) -> f32 {
+        self.start_time + self.steps.iter().map(|step| step.duration).sum::<f32>()
+    }
+
+    /// Whether every step is done at a time
+    pub fn is_finished(&self, now: f32) -> bool {
+        now >= self.end_time()
+    }
+
+    /// Pushes the whole tween back by some seconds, so it holds still for that long. Used for pausing
+    pub fn delay(&mut self, seconds: f32) {
+        self.start_time += seconds;
+    }
+
+    /// Replaces the tween with one that starts wherever this one is at a time, so there's no jump
+    pub fn retarget(&mut self, now: f32, to: T, duration: f32, easing: Easing) {
+        *self = Self::new(self.value(now), to, now, duration, easing);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stands in for `app.seconds`
+    struct FakeClock {
+        seconds: f32,
+    }
+
+    impl FakeClock {
+        fn advance(&mut self, seconds: f32) -> f32 {
+            self.seconds += seconds;
+            self.seconds
+        }
+    }
+
+    #[test]
+    fn easings_start_at_zero_and_end_at_one() {
+        for easing in [
+            Easing::Linear,
+            Easing::QuadOut,
+            Easing::CubicOut,
+            Easing::SineInOut,
+            Easing::BackOut,
+        ] {
+            assert!(easing.apply(0.0).abs() < 1e-5, "{:?}", easing);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-5, "{:?}", easing);
+        }
+    }
+
+    #[test]
+    fn easings_clamp_outside_the_animation() {
+        assert_eq!(Easing::CubicOut.apply(-1.0), 0.0);
+        assert_eq!(Easing::CubicOut.apply(2.0), 1.0);
+    }
+
+    #[test]
+    fn tween_moves_over_its_duration() {
+        let mut clock = FakeClock { seconds: 10.0 };
+        let tween = Tween::new(0.0, 4.0, clock.seconds, 2.0, Easing::Linear);
+        assert_eq!(tween.value(clock.seconds), 0.0);
+        assert_eq!(tween.value(clock.advance(1.0)), 2.0);
+        assert!(!tween.is_finished(clock.seconds));
+        assert_eq!(tween.value(clock.advance(1.0)), 4.0);
+        assert!(tween.is_finished(clock.seconds));
+        assert_eq!(tween.value(clock.advance(5.0)), 4.0);
+    }
+
+    #[test]
+    fn tween_holds_before_it_starts() {
+        let tween = Tween::new(1.0, 2.0, 5.0, 1.0, Easing::CubicOut);
+        assert_eq!(tween.value(3.0), 1.0);
+    }
+
+    #[test]
+    fn chained_steps_run_one_after_another() {
+        let mut clock = FakeClock { seconds: 0.0 };
+        let tween = Tween::fixed(0.0)
+            .then(10.0, 1.0, Easing::Linear)
+            .then(0.0, 2.0, Easing::Linear)
+            .starting_at(clock.seconds);
+        assert_eq!(tween.value(clock.advance(0.5)), 5.0);
+        assert_eq!(tween.value(clock.advance(0.5)), 10.0);
+        assert_eq!(tween.value(clock.advance(1.0)), 5.0);
+        assert_eq!(tween.end_time(), 3.0);
+        assert_eq!(tween.end_value(), 0.0);
+        assert_eq!(tween.value(clock.advance(1.0)), 0.0);
+    }
+
+    #[test]
+    fn delay_holds_the_value() {
+        let mut clock = FakeClock { seconds: 0.0 };
+        let mut tween = Tween::new(0.0, 10.0, clock.seconds, 1.0, Easing::Linear);
+        assert_eq!(tween.value(clock.advance(0.5)), 5.0);
+        // Paused for two seconds
+        tween.delay(2.0);
+        assert_eq!(tween.value(clock.advance(2.0)), 5.0);
+        assert!(!tween.is_finished(clock.seconds));
+        assert_eq!(tween.value(clock.advance(0.5)), 10.0);
+        assert!(tween.is_finished(clock.seconds));
+    }
+
+    #[test]
+    fn retarget_starts_from_the_current_value() {
+        let mut clock = FakeClock { seconds: 0.0 };
+        let mut tween = Tween::new(0.0, 10.0, clock.seconds, 1.0, Easing::Linear);
+        tween.retarget(clock.advance(0.5), -5.0, 1.0, Easing::Linear);
+        assert_eq!(tween.value(clock.seconds), 5.0);
+        assert_eq!(tween.value(clock.advance(0.5)), 0.0);
+        assert_eq!(tween.value(clock.advance(0.5)), -5.0);
+    }
+
+    #[test]
+    fn zero_duration_steps_jump_straight_to_the_end() {
+        let tween = Tween::new(0.0, 1.0, 0.0, 0.0, Easing::Linear);
+        assert_eq!(tween.value(0.0), 1.0);
+        assert!(tween.is_finished(0.0));
+    }
+
+    #[test]
+    fn vectors_and_colors_tween() {
+        let tween = Tween::new(
+            nalgebra_glm::vec3(0.0, 0.0, 0.0),
+            nalgebra_glm::vec3(2.0, 4.0, 6.0),
+            0.0,
+            1.0,
+            Easing::Linear,
+        );
+        assert_eq!(tween.value(0.5), nalgebra_glm::vec3(1.0, 2.0, 3.0));
+
+        let color = Tween::new(
+            nalgebra_glm::vec4(1.0, 0.0, 0.0, 1.0),
+            nalgebra_glm::vec4(0.0, 0.0, 1.0, 0.0),
+            0.0,
+            1.0,
+            Easing::Linear,
+        );
+        assert_eq!(color.value(0.5), nalgebra_glm::vec4(0.5, 0.0, 0.5, 0.5));
+    }
+}
@@ -4,13 +4,26 @@ mod scenes;
 use std::cell::RefCell;
 
 use apricot::app::run;
+use components::{
+    settings::{Settings, SETTINGS_PATH},
+    turn_report::EXPORT_REPORTS_FLAG,
+};
 use scenes::gameplay::Gameplay;
 
 fn main() -> Result<(), String> {
+    let settings = Settings::load(SETTINGS_PATH);
+    let export_reports = std::env::args().any(|arg| arg == EXPORT_REPORTS_FLAG);
+
     // Start Apricot's game loop
     run(
-        nalgebra_glm::I32Vec2::new(800, 600),
+        settings.window_size(),
         "Emergent Empire", // singular, empire
-        &|app| RefCell::new(Box::new(Gameplay::new(app))),
+        &|app| {
+            RefCell::new(Box::new(Gameplay::new(
+                app,
+                settings.clone(),
+                export_reports,
+            )))
+        },
     )
 }
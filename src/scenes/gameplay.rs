@@ -12,17 +12,82 @@ use apricot::{
     shadow_map::DirectionalLightSource,
 };
 use hecs::{Entity, World};
-use sdl2::keyboard::Scancode;
 
 use crate::components::{
+    anchor::{window_size, Anchor, Layout},
     button::{Button, Event, EventQueue},
+    calendar::{Calendar, Schedule},
+    camera_bookmarks::{
+        bookmark_actions, BookmarkAction, CameraBookmark, Focus, FocusHistory, BOOKMARK_COUNT,
+    },
+    camera_mode::{CameraMode, CameraModeControl},
+    construction::{
+        spawn_station, Category, Colony, Ship, Station, STARTING_CREDITS, STATION_DEFENSE,
+        STATION_SENSOR_RANGE,
+    },
+    construction_panel::{ConstructionAction, ConstructionPanel},
+    controls_screen::ControlsScreen,
+    galaxy::Galaxy,
+    galaxy_map::GalaxyMap,
+    habitability::{habitability_system, Habitability},
+    impostor::impostor_system,
+    info_panel::InfoPanel,
+    input::{Action, InputMap, CONTROLS_PATH},
+    label::{label_system, set_color, set_opacity},
+    lod::{add_icosphere_meshes, lod_system},
+    notifications::{NotificationFeed, NotificationKind},
+    options_screen::OptionsScreen,
+    outliner::Outliner,
+    pathfinding::{find_route, Route, SystemKnowledge},
     planet::Planet,
+    projection::{body_spheres, ScreenProjection},
+    save::{SaveFile, SAVE_PATH},
+    settings::Settings,
+    star::star_light_system,
+    text_metrics::TextMetrics,
+    time_control::TimeControl,
+    tooltip::{body_tooltip, Tooltip, TooltipTarget},
+    turn_report::{ReportAction, Stat, TurnReport, TurnReportPanel, TurnSnapshot},
+    tween::{Easing, Tween},
 };
 
 /// Object file data, used for meshes
 pub const QUAD_XY_DATA: &[u8] = include_bytes!("../../res/quad-xy.obj");
-pub const ICO_DATA: &[u8] = include_bytes!("../../res/ico-sphere.obj");
-pub const UV_DATA: &[u8] = include_bytes!("../../res/uv-sphere.obj");
+pub const STATION_DATA: &[u8] = include_bytes!("../../res/station.obj");
+
+/// Vertical field of view of the 3d camera, in radians
+const CAMERA_FOV: f32 = 0.65;
+/// How far away the 3d camera can see
+const CAMERA_FAR: f32 = 10000000.0;
+
+/// How many star systems are in the galaxy
+const SYSTEM_COUNT: usize = 40;
+/// Seed for generating the galaxy
+const GALAXY_SEED: u64 = 1984;
+/// How far along hyperlanes a fleet travels each turn, in galaxy map units
+const FLEET_SPEED: f32 = 25.0;
+
+/// Which view of the game is being shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum View {
+    /// The bodies of a single star system, in 3d
+    System,
+    /// Every star system in the galaxy, and the hyperlanes between them
+    Galaxy,
+}
+
+/// How long the camera takes to swoosh to a newly selected body, or between camera modes, in seconds
+const SWOOSH_DURATION: f32 = 1.0;
+/// How opaque orbit lines are drawn, except the selected body's
+const ORBIT_ALPHA: f32 = 0.2;
+/// How opaque the selected body's orbit line is drawn
+const SELECTED_ORBIT_ALPHA: f32 = 0.8;
+/// How long an orbit line takes to fade when the selection changes, in seconds
+const ORBIT_FADE_DURATION: f32 = 0.4;
+/// How far below the middle of the window centered panels start when they slide in, in pixels at a UI scale of 1
+const PANEL_SLIDE_DISTANCE: f32 = 40.0;
+/// How long centered panels take to slide in, in seconds
+const PANEL_SLIDE_DURATION: f32 = 0.3;
 
 /// Struct that contains info about the game state
 pub struct Gameplay {
@@ -30,8 +95,15 @@ pub struct Gameplay {
     world: World,
     /// The camera used for rendering 3d models
     camera_3d: Camera,
-    /// The sun's light source
+    /// Snapshot of the 3d camera, for drawing labels and icons over bodies
+    projection: ScreenProjection,
+    /// Placeholder light, only because the renderer's draw call takes one. Lighting and shadows actually come from
+    /// each star, see `star_light_system`, so its shadow map is never drawn to and is kept as small as possible
     directional_light: DirectionalLightSource,
+    /// The OpenGL id of the 3d shader program, for uploading star lights and shadow casters
+    program_3d_id: u32,
+    /// The OpenGL id of the 2d shader program, for fading labels
+    program_2d_id: u32,
     /// A bounding-volume hierarchy, a container that stores models and allows for efficient lookup for fast rendering
     bvh: BVH<Entity>,
 
@@ -43,8 +115,8 @@ pub struct Gameplay {
     selected_pos: nalgebra_glm::Vec3,
     /// The prev selected position, used for swoosh animation
     prev_selected_pos: nalgebra_glm::Vec3,
-    /// Animation key frame counter
-    transition: f32,
+    /// How far the camera has swooshed from the previously selected body to the selected body, from 0 to 1
+    swoosh: Tween<f32>,
 
     /// Up-down view angle
     phi: f32,
@@ -53,24 +125,219 @@ pub struct Gameplay {
     /// How far the camera swivels around the currently selected body
     distance: f32,
 
-    /// Used for enter key latch
-    prev_enter_state: bool,
+    /// How the camera is being controlled
+    camera_mode: CameraModeControl,
+    /// Where the camera is in free-fly mode
+    fly_pos: nalgebra_glm::Vec3,
+    /// Where the camera is this tick
+    camera_pos: nalgebra_glm::Vec3,
+    /// What the camera is looking at this tick
+    camera_lookat: nalgebra_glm::Vec3,
+    /// Where the camera was when the camera mode changed, used for swoosh animation between modes
+    prev_camera_pos: nalgebra_glm::Vec3,
+    /// What the camera was looking at when the camera mode changed, used for swoosh animation between modes
+    prev_camera_lookat: nalgebra_glm::Vec3,
+    /// How far the camera has swooshed from where it was when the camera mode changed, from 0 to 1
+    mode_swoosh: Tween<f32>,
+
+    /// Saved camera positions, indexed by their bookmark action
+    bookmarks: [Option<CameraBookmark>; BOOKMARK_COUNT],
+    /// The bodies the camera looked at, for going back and forward between them
+    history: FocusHistory,
+
+    /// What each action is bound to, and which actions are held down
+    input: InputMap,
+    /// Screen for rebinding actions
+    controls_screen: ControlsScreen,
+    /// The user's settings, loaded at startup
+    settings: Settings,
+    /// Screen for changing settings
+    options_screen: OptionsScreen,
+
     /// How many planets there are
     bodies: Vec<Entity>,
+    /// How opaque each body's orbit line is, fading between the selected and unselected opacity
+    orbit_fades: HashMap<Entity, Tween<f32>>,
+
+    /// Every colony in the galaxy, whether or not its system is in the world
+    colonies: Vec<Colony>,
+    /// Credits the empire has to spend on construction
+    credits: u32,
+    /// Shows the construction queue of the selected colony
+    construction_panel: ConstructionPanel,
+
+    /// Every star system, and the hyperlanes between them
+    galaxy: Galaxy,
+    /// Index into the galaxy's systems of the system whose bodies are in the world
+    current_system: usize,
+    /// What the empire knows about each system in the galaxy
+    knowledge: Vec<SystemKnowledge>,
+    /// The route from the current system to the system under the mouse on the galaxy map
+    planned_route: Option<Route>,
+    /// Which view is being shown
+    view: View,
+    /// The galaxy map view
+    galaxy_map: GalaxyMap,
 
     /// Queue of events generated by the GUI this current tick
     event_queue: Arc<EventQueue>,
+    /// Panel showing data about the selected body
+    info_panel: InfoPanel,
+    /// Tree panel of every body in the system, for selecting bodies
+    outliner: Outliner,
+    /// What happened each turn, shown in the corner of the HUD
+    notifications: NotificationFeed,
+    /// Explains whatever the mouse is resting on
+    tooltip: Tooltip,
+    /// The size of the font the UI was started with. The font and UI scale only change on restart
+    metrics: TextMetrics,
+    /// The window size the UI was last laid out for
+    laid_out_window_size: nalgebra_glm::Vec2,
+    /// How far below the middle of the window the centered panels are, while they slide in
+    panel_slide: Tween<f32>,
+    /// Whether a centered panel was open last frame, for sliding panels in when one opens
+    panel_was_open: bool,
 
     turn: usize,
-    turn_transition_time: f32,
+    /// How far the current turn has played out, from 0 to 1
+    turn_progress: Tween<f32>,
+    /// When the last tick was, in seconds, for holding the turn still while a screen is open
+    last_tick_seconds: f32,
+    /// Whether the game is turn-based or real-time, and how fast
+    time_control: TimeControl,
+    /// Maps turns to dates
+    calendar: Calendar,
+    /// Messages scheduled to be shown on a date
+    schedule: Schedule<&'static str>,
+    /// Shows what changed during the latest turn
+    turn_report: TurnReportPanel,
+    /// The empire's stats at the end of the last turn, for the next report to compare against
+    last_snapshot: TurnSnapshot,
+    /// Whether every turn's report is exported as it's made
+    export_reports: bool,
 }
 
 impl Scene for Gameplay {
     /// Update the scene every tick
     fn update(&mut self, app: &App) {
-        // Update all the buttons
+        self.layout_ui(app);
+        self.input.update(app);
+        if self.input.pressed(Action::Controls) && !self.controls_screen.is_rebinding() {
+            self.controls_screen.open = !self.controls_screen.open;
+            self.options_screen.open = false;
+        }
+        if self.input.pressed(Action::Options) && !self.controls_screen.is_rebinding() {
+            self.options_screen.open = !self.options_screen.open;
+            self.controls_screen.open = false;
+        }
+        if self.input.pressed(Action::TurnReport)
+            && !self.controls_screen.is_rebinding()
+            && self.turn_report.report().is_some()
+        {
+            self.turn_report.open = !self.turn_report.open;
+        }
+        if self.input.pressed(Action::Construction)
+            && !self.controls_screen.is_rebinding()
+            && (self.construction_panel.open || self.selected_colony().is_some())
+        {
+            self.construction_panel.open = !self.construction_panel.open;
+        }
+        let screen_open = self.controls_screen.open
+            || self.options_screen.open
+            || self.turn_report.open
+            || self.construction_panel.open;
+
+        let next_turn_disabled = if self.time_control.is_running() {
+            Some("turns play out on their own in real-time")
+        } else if !self.turn_finished(app) {
+            Some("resolving turn")
+        } else {
+            None
+        };
         for (_entity, button) in self.world.query_mut::<&mut Button>() {
-            button.update(app);
+            if button.id() == "next-turn" {
+                button.set_disabled(next_turn_disabled);
+            }
+        }
+
+        if self.controls_screen.open {
+            self.controls_screen.update(app, &mut self.input);
+        } else if self.options_screen.open {
+            self.options_screen.update(app, &mut self.settings);
+        } else if self.turn_report.open {
+            if self.turn_report.update(app) == Some(ReportAction::Export) {
+                self.export_turn_report();
+            }
+        } else if self.construction_panel.open {
+            match self.selected_colony() {
+                Some(colony) => {
+                    let ships = self
+                        .world
+                        .query::<&Ship>()
+                        .iter()
+                        .filter(|(_entity, ship)| ship.system == self.colonies[colony].system)
+                        .count();
+                    let bodies = self.natural_body_names();
+                    if let Some(action) = self.construction_panel.update(
+                        app,
+                        &self.colonies[colony],
+                        &bodies,
+                        self.credits,
+                        ships,
+                    ) {
+                        self.construction_action(colony, action);
+                    }
+                }
+                // The selection moved off the colony, like when a turn report's body was clicked
+                None => self.construction_panel.open = false,
+            }
+        } else {
+            // Update all the buttons
+            for (_entity, button) in self.world.query_mut::<&mut Button>() {
+                button.update(app);
+            }
+            self.notifications.update(app, &self.world);
+            match self.view {
+                View::System => {
+                    self.info_panel.update(
+                        app,
+                        &self.world,
+                        self.bodies[self.selection],
+                        self.selected_colony().map(|colony| &self.colonies[colony]),
+                    );
+                    self.outliner.update(
+                        app,
+                        &self.input,
+                        &self.world,
+                        self.bodies[self.selection],
+                    );
+                }
+                View::Galaxy => {
+                    self.galaxy_map.update(
+                        app,
+                        &self.input,
+                        &self.galaxy,
+                        self.current_system,
+                        self.settings.camera_sensitivity(),
+                    );
+                    self.planned_route = self.galaxy_map.hovered().and_then(|destination| {
+                        let positions: Vec<nalgebra_glm::Vec3> = self
+                            .galaxy
+                            .systems
+                            .iter()
+                            .map(|system| system.position)
+                            .collect();
+                        find_route(
+                            &positions,
+                            &self.galaxy.lanes,
+                            &self.knowledge,
+                            self.current_system,
+                            destination,
+                            FLEET_SPEED,
+                        )
+                    });
+                }
+            }
         }
 
         // Loop through the events in the event queue and respond to them
@@ -78,97 +345,233 @@ impl Scene for Gameplay {
             match event {
                 // Handle all the buttons
                 Event::ButtonClicked(id) => match id {
-                    "next-turn" => {
-                        if (app.seconds - self.turn_transition_time) >= 1.0 {
-                            self.turn += 1;
-                            self.turn_transition_time = app.seconds;
-                            println!("doing the next turn!")
-                        }
-                    }
+                    "next-turn" => self.next_turn(app),
                     _ => panic!("unknown button id: {:?}", id),
                 },
+                Event::BodyClicked(entity) => {
+                    if let Some(index) = self.bodies.iter().position(|body| *body == entity) {
+                        self.view = View::System;
+                        self.select(app, index);
+                    }
+                }
+                Event::SystemClicked(system) => {
+                    if system != self.current_system {
+                        self.enter_system(app, system);
+                        self.history.push(Focus {
+                            system,
+                            selection: self.selection,
+                        });
+                    }
+                    self.view = View::System;
+                }
             }
         }
 
-        self.control(app);
+        // Keys pressed on the controls and options screens are for those screens, not for playing
+        if !screen_open {
+            self.time_control.update(&self.input);
+            if self.input.pressed(Action::QuickSave) {
+                self.save_game();
+            }
+            if self.input.pressed(Action::EventLog) {
+                self.notifications.expanded = !self.notifications.expanded;
+            }
+            if self.input.pressed(Action::GalaxyMap) {
+                self.view = match self.view {
+                    View::System => View::Galaxy,
+                    View::Galaxy => View::System,
+                };
+            }
+            if self.input.pressed(Action::EndTurn)
+                && !self.time_control.is_running()
+                && self.turn_finished(app)
+            {
+                self.next_turn(app);
+            }
+            if self.view == View::System {
+                if self.camera_mode.update(&self.input) {
+                    self.change_camera_mode(app);
+                }
+                for action in bookmark_actions(&self.input) {
+                    self.bookmark_action(app, action);
+                }
+                self.control(app);
+            }
+        }
+        // Screens pause the game, so the turn playing out holds still behind them
+        if screen_open {
+            self.turn_progress
+                .delay(app.seconds - self.last_tick_seconds);
+        } else if self.time_control.is_running() && self.turn_finished(app) {
+            self.next_turn(app);
+        }
+        self.last_tick_seconds = app.seconds;
         self.planet_system(app, 0);
         self.planet_system(app, 1);
         self.planet_system(app, 2);
+        self.planet_system(app, 3);
         self.orbit_system(app);
         self.camera_update(app);
+        lod_system(&mut self.world, &self.projection);
+        let hovered = if screen_open {
+            None
+        } else {
+            self.hovered_tooltip(app)
+        };
+        self.tooltip.update(app, hovered);
     }
 
     /// Render the scene to the screen when time allows
     fn render(&mut self, app: &App) {
-        app.renderer.set_camera(self.camera_3d);
-        app.renderer.directional_light_system(
-            &mut self.directional_light,
-            &mut self.world,
-            &self.bvh,
-        );
-        app.renderer.render_3d_models_system(
-            &mut self.world,
-            &self.directional_light,
-            &self.bvh,
-            false,
-        );
-
         let font = app.renderer.get_font_id_from_name("font").unwrap();
-        app.renderer.set_font(font);
-        for (entity, planet) in self.world.query::<&Planet>().iter() {
-            if entity == self.bodies[self.selection] {
-                app.renderer
-                    .draw_text(nalgebra_glm::vec2(10.0, 10.0), &planet.name);
+        let icon_texture_id = app.renderer.get_texture_id_from_name("body-icon").unwrap();
+        self.slide_panels(app);
+        if self.controls_screen.open {
+            app.renderer.set_font(font);
+            self.controls_screen.render(app, &self.input);
+            return;
+        }
+        if self.options_screen.open {
+            app.renderer.set_font(font);
+            self.options_screen.render(app, &self.settings);
+            return;
+        }
+        match self.view {
+            View::System => {
+                app.renderer.set_camera(self.camera_3d);
+                star_light_system(&self.world, self.program_3d_id);
+                app.renderer.render_3d_models_system(
+                    &mut self.world,
+                    &self.directional_light,
+                    &self.bvh,
+                    false,
+                );
+
+                app.renderer.set_font(font);
+                impostor_system(app, &self.world, &self.projection, icon_texture_id);
+                label_system(
+                    app,
+                    &self.world,
+                    &self.projection,
+                    self.bodies[self.selection],
+                    &self.metrics,
+                    self.program_2d_id,
+                );
+                self.info_panel.render(app);
+                self.outliner.render(app, self.bodies[self.selection]);
+            }
+            View::Galaxy => {
+                app.renderer.set_font(font);
+                self.galaxy_map.render(
+                    app,
+                    &self.galaxy,
+                    self.current_system,
+                    self.planned_route.as_ref(),
+                    icon_texture_id,
+                );
             }
         }
 
         for (_entity, button) in self.world.query_mut::<&mut Button>() {
-            button.render(app);
+            button.render(app, self.program_2d_id);
         }
 
+        // Progress bar just below the next-turn button, shown while the turn plays out
+        if !self.turn_finished(app) {
+            let scale = self.metrics.ui_scale;
+            let bar = Layout::new(
+                Anchor::BottomRight,
+                nalgebra_glm::vec2(10.0, 22.0),
+                nalgebra_glm::vec2(90.0, 6.0),
+            );
+            let pos = bar.position(window_size(app), scale);
+            let size = bar.size * scale;
+            let white_texture_id = app.renderer.get_texture_id_from_name("white").unwrap();
+            let texture_rect = Rectangle::new(0.0, 0.0, 8.0, 8.0);
+            set_opacity(self.program_2d_id, 0.25);
+            app.renderer.copy_texture(
+                Rectangle::new(pos.x, pos.y, size.x, size.y),
+                white_texture_id,
+                texture_rect,
+            );
+            set_opacity(self.program_2d_id, 1.0);
+            app.renderer.copy_texture(
+                Rectangle::new(
+                    pos.x,
+                    pos.y,
+                    size.x * self.turn_progress.value(app.seconds),
+                    size.y,
+                ),
+                white_texture_id,
+                texture_rect,
+            );
+        }
+
+        // HUD text is laid out from the bottom-right corner, around the next-turn button
+        let hud_text_pos = |margin_x: f32, margin_y: f32, width: f32| {
+            Layout::new(
+                Anchor::BottomRight,
+                nalgebra_glm::vec2(margin_x, margin_y),
+                nalgebra_glm::vec2(width, 20.0),
+            )
+            .position(window_size(app), self.metrics.ui_scale)
+        };
         app.renderer.draw_text(
-            nalgebra_glm::vec2(
-                app.window_size.x as f32 - 90.0,
-                app.window_size.y as f32 - 20.0,
-            ),
+            hud_text_pos(10.0, 0.0, 80.0),
             format!("turn: {}", self.turn).to_string().as_str(),
         );
+        app.renderer.draw_text(
+            hud_text_pos(100.0, 0.0, 120.0),
+            self.calendar.format_turn(self.turn).as_str(),
+        );
+        app.renderer.draw_text(
+            hud_text_pos(10.0, 120.0, 90.0),
+            self.time_control.speed.label(),
+        );
+        app.renderer.draw_text(
+            hud_text_pos(10.0, 140.0, 90.0),
+            self.camera_mode.mode.label(),
+        );
+
+        if self.view == View::System {
+            app.renderer.render_3d_line_paths(&self.world);
+        }
 
-        app.renderer.render_3d_line_paths(&self.world);
+        self.notifications
+            .render(app, &self.world, self.program_2d_id);
+        if self.turn_report.open {
+            self.turn_report.render(app, self.program_2d_id);
+        }
+        if self.construction_panel.open {
+            self.construction_panel.render(app, self.program_2d_id);
+        }
+        self.tooltip.render(app, self.program_2d_id);
     }
 }
 
 impl Gameplay {
     /// Constructs a new Gameplay struct with everything setup
     /// TODO: Most of this stuff will need to be moved to the init scene. Remind me to make an issue for this!
-    pub fn new(app: &App) -> Self {
+    pub fn new(app: &App, settings: Settings, export_reports: bool) -> Self {
         let mut world = World::new();
 
         // Add programs to the renderer
-        app.renderer.add_program(
-            create_program(
-                include_str!("../shaders/3d.vert"),
-                include_str!("../shaders/3d.frag"),
-            )
-            .unwrap(),
-            Some("3d"),
-        );
-        app.renderer.add_program(
-            create_program(
-                include_str!("../shaders/2d.vert"),
-                include_str!("../shaders/2d.frag"),
-            )
-            .unwrap(),
-            Some("2d"),
-        );
-        app.renderer.add_program(
-            create_program(
-                include_str!("../shaders/shadow.vert"),
-                include_str!("../shaders/shadow.frag"),
-            )
-            .unwrap(),
-            Some("shadow"),
-        );
+        let program_3d = create_program(
+            include_str!("../shaders/3d.vert"),
+            include_str!("../shaders/3d.frag"),
+        )
+        .unwrap();
+        let program_3d_id = program_3d.id();
+        app.renderer.add_program(program_3d, Some("3d"));
+        let program_2d = create_program(
+            include_str!("../shaders/2d.vert"),
+            include_str!("../shaders/2d.frag"),
+        )
+        .unwrap();
+        let program_2d_id = program_2d.id();
+        app.renderer.add_program(program_2d, Some("2d"));
+        set_opacity(program_2d_id, 1.0);
+        set_color(program_2d_id, nalgebra_glm::vec3(1.0, 1.0, 1.0));
         app.renderer.add_program(
             create_program(
                 include_str!("../shaders/2d.vert"),
@@ -197,8 +600,9 @@ impl Gameplay {
         // Setup the mesh manager
         app.renderer
             .add_mesh_from_obj(QUAD_XY_DATA, Some("quad-xy"));
-        app.renderer.add_mesh_from_obj(UV_DATA, Some("uv"));
-        app.renderer.add_mesh_from_obj(ICO_DATA, Some("ico"));
+        app.renderer
+            .add_mesh_from_obj(STATION_DATA, Some("station"));
+        add_icosphere_meshes(&app.renderer);
 
         // Setup the texture manager
         app.renderer
@@ -207,106 +611,111 @@ impl Gameplay {
             .add_texture_from_png("res/earth.png", Some("earth"));
         app.renderer
             .add_texture_from_png("res/moon.png", Some("moon"));
+        app.renderer
+            .add_texture_from_png("res/station.png", Some("station"));
+        app.renderer
+            .add_texture_from_png("res/body-icon.png", Some("body-icon"));
         app.renderer
             .add_texture_from_png("res/next-turn.png", Some("next-turn"));
         app.renderer
             .add_texture_from_png("res/next-turn-hover.png", Some("next-turn-hover"));
+        app.renderer
+            .add_texture_from_png("res/white.png", Some("white"));
+        app.renderer
+            .add_texture_from_png("res/panel.png", Some("panel"));
 
         // Setup the font manager
-        app.renderer
-            .add_font("res/Consolas.ttf", "font", 16, sdl2::ttf::FontStyle::NORMAL);
+        let metrics = settings.text_metrics();
+        let font_points = TextMetrics::points(settings.font_size(), settings.ui_scale());
+        app.renderer.add_font(
+            "res/Consolas.ttf",
+            "font",
+            font_points,
+            sdl2::ttf::FontStyle::NORMAL,
+        );
+        // Rich text switches between these for bold and italic
+        for (name, style) in [
+            ("font-bold", sdl2::ttf::FontStyle::BOLD),
+            ("font-italic", sdl2::ttf::FontStyle::ITALIC),
+            (
+                "font-bold-italic",
+                sdl2::ttf::FontStyle::BOLD | sdl2::ttf::FontStyle::ITALIC,
+            ),
+        ] {
+            app.renderer
+                .add_font("res/Consolas.ttf", name, font_points, style);
+        }
 
         let mut bvh = BVH::<Entity>::new();
 
-        let sun_entity = Planet::new(
+        let galaxy = Galaxy::generate(settings.galaxy_shape(), SYSTEM_COUNT, GALAXY_SEED);
+        let bodies = galaxy.spawn_system(
+            galaxy.home,
             &mut world,
             &app.renderer,
             &mut bvh,
-            true,
-            Entity::DANGLING,
-            0,
-            110.0,
-            0.0,
-            0.0,
-            0.0,
-            app.renderer.get_texture_id_from_name("sun").unwrap(),
-            "Sun",
+            settings.orbit_segments(),
         );
+        habitability_system(&mut world);
+        let mut knowledge = vec![SystemKnowledge::default(); galaxy.systems.len()];
+        knowledge[galaxy.home].explored = true;
+        // The empire starts out with a single colony on earth
+        let home_colony = bodies
+            .iter()
+            .position(|body| world.get::<&Planet>(*body).unwrap().name == "Earth")
+            .unwrap();
+        let home_habitability = world
+            .get::<&Habitability>(bodies[home_colony])
+            .map_or(0.0, |habitability| habitability.score);
 
-        let mercury_entity = Planet::new(
-            &mut world,
-            &app.renderer,
-            &mut bvh,
-            false,
-            sun_entity,
-            1,
-            0.375,
-            10000.0,
-            1.0,
-            0.0027,
-            app.renderer.get_texture_id_from_name("moon").unwrap(),
-            "Mercury",
-        );
-
-        let planet_entity = Planet::new(
-            &mut world,
-            &app.renderer,
-            &mut bvh,
-            false,
-            sun_entity,
-            1,
-            1.,
-            20000.0,
-            1.0,
-            0.0027,
-            app.renderer.get_texture_id_from_name("earth").unwrap(),
-            "Earth",
-        );
+        let event_queue = Arc::new(EventQueue::new());
 
-        let moon_entity = Planet::new(
-            &mut world,
-            &app.renderer,
-            &mut bvh,
-            false,
-            planet_entity,
-            2,
-            0.72,
-            60.0,
-            0.0749,
-            0.0749,
-            app.renderer.get_texture_id_from_name("moon").unwrap(),
-            "Moon",
+        let calendar = settings.calendar();
+        let mut schedule = Schedule::new();
+        schedule.schedule(
+            &calendar,
+            calendar.date_of_turn(0) + chrono::Months::new(12),
+            "A year has passed since the founding of your empire",
         );
 
-        let event_queue = Arc::new(EventQueue::new());
-
         world.spawn((Button::new(
             "next-turn",
-            Rectangle::new(
-                app.window_size.x as f32 - 100.0,
-                app.window_size.y as f32 - 120.0,
-                90.0,
-                90.0,
+            Layout::new(
+                Anchor::BottomRight,
+                nalgebra_glm::vec2(10.0, 30.0),
+                nalgebra_glm::vec2(90.0, 90.0),
             ),
             app.renderer.get_texture_id_from_name("next-turn").unwrap(),
             app.renderer
                 .get_texture_id_from_name("next-turn-hover")
                 .unwrap(),
             event_queue.clone(),
+        )
+        .with_tooltip(
+            "[b]next turn[/b]\nplays out the next turn, moving bodies along their orbits",
         ),));
 
-        Self {
+        let mut gameplay = Self {
             world,
             camera_3d: Camera::new(
                 nalgebra_glm::vec3(1.0, 0.0, 1.0),
                 nalgebra_glm::vec3(0.0, 0.0, 0.0),
                 nalgebra_glm::vec3(0.0, 0.0, 1.0),
                 ProjectionKind::Perspective {
-                    fov: 0.65,
-                    far: 10000000.0,
+                    fov: CAMERA_FOV,
+                    far: CAMERA_FAR,
                 },
             ),
+            projection: ScreenProjection::new(
+                nalgebra_glm::vec3(1.0, 0.0, 1.0),
+                nalgebra_glm::vec3(0.0, 0.0, 0.0),
+                CAMERA_FOV,
+                CAMERA_FAR,
+                nalgebra_glm::vec2(app.window_size.x as f32, app.window_size.y as f32),
+            ),
             bvh,
+            program_3d_id,
+            program_2d_id,
             directional_light: DirectionalLightSource::new(
                 Camera::new(
                     nalgebra_glm::vec3(0.0, 0.0, 0.0),
@@ -323,53 +732,789 @@ impl Gameplay {
                     },
                 ),
                 nalgebra_glm::vec3(-1.0, 0.0, 0.0),
-                1024,
+                1,
             ),
 
             selection: 2,
             selected_pos: nalgebra_glm::vec3(0.0, 0.0, 0.0),
             prev_selected_pos: nalgebra_glm::vec3(0.0, 0.0, 0.0),
-            transition: 1.0,
+            swoosh: Tween::fixed(1.0),
             selected_body_radius: 0.0,
             phi: 2.5,
             theta: 0.0,
             distance: 20.0,
-            prev_enter_state: false,
-            bodies: vec![sun_entity, mercury_entity, planet_entity, moon_entity],
+
+            camera_mode: CameraModeControl::new(),
+            fly_pos: nalgebra_glm::vec3(1.0, 0.0, 1.0),
+            camera_pos: nalgebra_glm::vec3(1.0, 0.0, 1.0),
+            camera_lookat: nalgebra_glm::vec3(0.0, 0.0, 0.0),
+            prev_camera_pos: nalgebra_glm::vec3(1.0, 0.0, 1.0),
+            prev_camera_lookat: nalgebra_glm::vec3(0.0, 0.0, 0.0),
+            mode_swoosh: Tween::fixed(1.0),
+
+            bookmarks: [None; BOOKMARK_COUNT],
+            history: FocusHistory::new(Focus {
+                system: galaxy.home,
+                selection: 2,
+            }),
+
+            input: InputMap::load(CONTROLS_PATH),
+            controls_screen: ControlsScreen::new(
+                nalgebra_glm::vec2(10.0, 10.0) * metrics.ui_scale,
+                metrics,
+            ),
+            settings,
+            options_screen: OptionsScreen::new(
+                nalgebra_glm::vec2(10.0, 10.0) * metrics.ui_scale,
+                metrics,
+            ),
+
+            colonies: vec![Colony::new(
+                galaxy.home,
+                home_colony,
+                "Earth".to_string(),
+                home_habitability,
+            )],
+            credits: STARTING_CREDITS,
+            construction_panel: ConstructionPanel::new(nalgebra_glm::vec2(0.0, 0.0), metrics),
+
+            bodies,
+            orbit_fades: HashMap::new(),
+
+            current_system: galaxy.home,
+            knowledge,
+            planned_route: None,
+            galaxy,
+            view: View::System,
+            galaxy_map: GalaxyMap::new(app, metrics, event_queue.clone()),
 
             event_queue: event_queue.clone(),
+            info_panel: InfoPanel::new(nalgebra_glm::vec2(0.0, 0.0), metrics, event_queue.clone()),
+            outliner: Outliner::new(nalgebra_glm::vec2(0.0, 0.0), metrics, event_queue.clone()),
+            notifications: NotificationFeed::new(
+                nalgebra_glm::vec2(0.0, 0.0),
+                metrics,
+                event_queue.clone(),
+            ),
+            tooltip: Tooltip::new(metrics),
+            metrics,
+            // Laid out on the first update
+            laid_out_window_size: nalgebra_glm::vec2(0.0, 0.0),
+            panel_slide: Tween::fixed(0.0),
+            panel_was_open: false,
 
             turn: 0,
-            turn_transition_time: 1.0,
+            turn_progress: Tween::fixed(1.0),
+            last_tick_seconds: 0.0,
+            time_control: TimeControl::new(),
+            calendar,
+            schedule,
+            turn_report: TurnReportPanel::new(nalgebra_glm::vec2(0.0, 0.0), metrics),
+            last_snapshot: TurnSnapshot::default(),
+            export_reports,
+        };
+
+        match SaveFile::load(SAVE_PATH) {
+            Ok(save) => gameplay.load_game(app, save),
+            Err(err) => println!("starting a new game, could not load save: {}", err),
+        }
+        gameplay.last_snapshot = gameplay.snapshot();
+        gameplay
+    }
+
+    /// Slides the centered panels up into the middle of the window when one opens
+    fn slide_panels(&mut self, app: &App) {
+        let panel_open = self.turn_report.open || self.construction_panel.open;
+        if panel_open && !self.panel_was_open {
+            self.panel_slide = Tween::new(
+                self.metrics.scale(PANEL_SLIDE_DISTANCE),
+                0.0,
+                app.seconds,
+                PANEL_SLIDE_DURATION,
+                Easing::BackOut,
+            );
+        }
+        self.panel_was_open = panel_open;
+        let center =
+            window_size(app) / 2.0 + nalgebra_glm::vec2(0.0, self.panel_slide.value(app.seconds));
+        self.turn_report.set_center(center);
+        self.construction_panel.set_center(center);
+    }
+
+    /// Moves the UI elements to where their layouts put them, if the window was resized
+    fn layout_ui(&mut self, app: &App) {
+        let window_size = window_size(app);
+        let scale = self.metrics.ui_scale;
+        if window_size == self.laid_out_window_size {
+            return;
+        }
+        self.laid_out_window_size = window_size;
+
+        for (_entity, button) in self.world.query_mut::<&mut Button>() {
+            button.relayout(window_size, scale);
+        }
+        self.info_panel.set_position(
+            Layout::new(
+                Anchor::TopLeft,
+                nalgebra_glm::vec2(10.0, 10.0),
+                nalgebra_glm::vec2(0.0, 0.0),
+            )
+            .position(window_size, scale),
+        );
+        self.outliner.set_position(
+            Layout::new(
+                Anchor::TopRight,
+                nalgebra_glm::vec2(10.0, 10.0),
+                nalgebra_glm::vec2(190.0, 0.0),
+            )
+            .position(window_size, scale),
+        );
+        self.controls_screen.set_window_width(window_size.x);
+        self.notifications.set_position(
+            Layout::new(
+                Anchor::BottomLeft,
+                nalgebra_glm::vec2(10.0, 10.0),
+                nalgebra_glm::vec2(0.0, 0.0),
+            )
+            .position(window_size, scale),
+        );
+    }
+
+    /// Writes the game to the save file
+    fn save_game(&mut self) {
+        let save = SaveFile {
+            turn: self.turn,
+            current_system: self.current_system,
+            bookmarks: self.bookmarks,
+            credits: self.credits,
+            colonies: self.colonies.clone(),
+            ships: self
+                .world
+                .query::<&Ship>()
+                .iter()
+                .map(|(_entity, ship)| ship.clone())
+                .collect(),
+        };
+        match save.save(SAVE_PATH) {
+            Ok(()) => self.notifications.post(
+                self.turn,
+                NotificationKind::GameSaved,
+                format!("saved the game to {}", SAVE_PATH),
+                None,
+            ),
+            Err(err) => self.notifications.post(
+                self.turn,
+                NotificationKind::SaveFailed,
+                format!("could not save the game: {}", err),
+                None,
+            ),
+        }
+    }
+
+    /// The empire's stats right now, for the turn report
+    fn snapshot(&self) -> TurnSnapshot {
+        let count = |f: fn(&SystemKnowledge) -> bool| {
+            self.knowledge
+                .iter()
+                .filter(|knowledge| f(knowledge))
+                .count() as f32
+        };
+        let stations: usize = self
+            .colonies
+            .iter()
+            .map(|colony| colony.stations.len())
+            .sum();
+        TurnSnapshot {
+            stats: vec![
+                Stat {
+                    name: "systems explored",
+                    value: count(|knowledge| knowledge.explored),
+                },
+                Stat {
+                    name: "hostile systems known",
+                    value: count(|knowledge| knowledge.explored && knowledge.hostile),
+                },
+                Stat {
+                    name: "credits",
+                    value: self.credits as f32,
+                },
+                Stat {
+                    name: "colonies",
+                    value: self.colonies.len() as f32,
+                },
+                Stat {
+                    name: "population (millions)",
+                    value: self
+                        .colonies
+                        .iter()
+                        .map(|colony| colony.population.round())
+                        .sum(),
+                },
+                Stat {
+                    name: "buildings",
+                    value: self
+                        .colonies
+                        .iter()
+                        .map(|colony| colony.buildings.len())
+                        .sum::<usize>() as f32,
+                },
+                Stat {
+                    name: "orbital stations",
+                    value: stations as f32,
+                },
+                Stat {
+                    name: "station defense",
+                    value: self
+                        .knowledge
+                        .iter()
+                        .map(|knowledge| knowledge.defense)
+                        .sum::<u32>() as f32,
+                },
+                Stat {
+                    name: "ships",
+                    value: self.world.query::<&Ship>().iter().count() as f32,
+                },
+            ],
+        }
+    }
+
+    /// Index into the colonies of the selected body's colony, if it has one
+    fn selected_colony(&self) -> Option<usize> {
+        self.colonies.iter().position(|colony| {
+            colony.system == self.current_system && colony.body == self.selection
+        })
+    }
+
+    /// Names of the current system's natural bodies, in spawn order. Stations are left out, they're spawned after
+    /// every natural body
+    fn natural_body_names(&self) -> Vec<String> {
+        self.bodies
+            .iter()
+            .filter(|body| self.world.get::<&Station>(**body).is_err())
+            .map(|body| self.world.get::<&Planet>(*body).unwrap().name.clone())
+            .collect()
+    }
+
+    /// Adds, reorders or cancels a project in a colony's construction queue
+    fn construction_action(&mut self, colony: usize, action: ConstructionAction) {
+        let colony = &mut self.colonies[colony];
+        let mut message = None;
+        match action {
+            ConstructionAction::Build(project, target) => {
+                message = colony.enqueue(project, target, &mut self.credits).err();
+            }
+            ConstructionAction::MoveUp(i) => colony.queue.move_up(i),
+            ConstructionAction::MoveDown(i) => colony.queue.move_down(i),
+            ConstructionAction::Cancel(i) => self.credits += colony.queue.cancel(i),
+            ConstructionAction::StationTarget(_) | ConstructionAction::Close => {}
+        }
+        self.construction_panel.set_message(message);
+    }
+
+    /// Pays each colony's income and does a turn of work on its construction queue, spawning whatever gets finished
+    fn resolve_construction(&mut self, app: &App) {
+        for i in 0..self.colonies.len() {
+            self.colonies[i].grow();
+            self.credits += self.colonies[i].income();
+            let Some(item) = self.colonies[i].queue.advance() else {
+                continue;
+            };
+            let project = item.project;
+
+            let colony = &mut self.colonies[i];
+            let in_world = colony.system == self.current_system;
+            let mut target = in_world.then(|| self.bodies[colony.body]);
+            let text = match project.category() {
+                Category::Building => {
+                    colony.buildings.push(project);
+                    format!("{} finished building a {}", colony.name, project.name())
+                }
+                Category::Station => {
+                    let index = colony.stations_around(item.target);
+                    colony.stations.push(item.target);
+                    if in_world {
+                        let station = spawn_station(
+                            &mut self.world,
+                            &app.renderer,
+                            &mut self.bvh,
+                            self.settings.orbit_segments(),
+                            self.bodies[item.target],
+                            index,
+                        );
+                        self.bodies.push(station);
+                        target = Some(station);
+                    }
+                    format!("{} finished building an {}", colony.name, project.name())
+                }
+                Category::Ship => {
+                    let ship = Ship {
+                        name: format!(
+                            "{} {}",
+                            project.name(),
+                            self.world.query::<&Ship>().iter().count() + 1
+                        ),
+                        system: colony.system,
+                    };
+                    let text = format!("{} launched {}", colony.name, ship.name);
+                    self.world.spawn((ship,));
+                    text
+                }
+            };
+            self.notifications.post(
+                self.turn,
+                NotificationKind::ConstructionComplete,
+                text,
+                target,
+            );
+        }
+    }
+
+    /// Totals the defense of the stations in each system, which route planning favors
+    fn station_defense(&mut self) {
+        for knowledge in self.knowledge.iter_mut() {
+            knowledge.defense = 0;
+        }
+        for colony in self.colonies.iter() {
+            self.knowledge[colony.system].defense += colony.stations.len() as u32 * STATION_DEFENSE;
+        }
+    }
+
+    /// Explores every system within sensor range of a station, along the hyperlanes
+    fn station_sensors(&mut self) {
+        let mut seen = vec![false; self.galaxy.systems.len()];
+        let mut frontier: Vec<usize> = self
+            .colonies
+            .iter()
+            .filter(|colony| !colony.stations.is_empty())
+            .map(|colony| colony.system)
+            .collect();
+        for &system in frontier.iter() {
+            seen[system] = true;
+        }
+        for _jump in 0..STATION_SENSOR_RANGE {
+            let mut next = vec![];
+            for &system in frontier.iter() {
+                for neighbor in self.galaxy.neighbors(system) {
+                    if !seen[neighbor] {
+                        seen[neighbor] = true;
+                        next.push(neighbor);
+                    }
+                }
+            }
+            frontier = next;
+        }
+
+        for (system, seen) in seen.into_iter().enumerate() {
+            if seen && !self.knowledge[system].explored {
+                self.knowledge[system].explored = true;
+                self.notifications.post(
+                    self.turn,
+                    NotificationKind::SystemExplored,
+                    format!(
+                        "station sensors picked up {}",
+                        self.galaxy.systems[system].name
+                    ),
+                    None,
+                );
+            }
+        }
+    }
+
+    /// Writes the latest turn report to a text file
+    fn export_turn_report(&mut self) {
+        let Some(report) = self.turn_report.report() else {
+            return;
+        };
+        match report.export() {
+            Ok(path) => self.notifications.post(
+                self.turn,
+                NotificationKind::ReportExported,
+                format!("exported the turn report to {}", path),
+                None,
+            ),
+            Err(err) => self.notifications.post(
+                self.turn,
+                NotificationKind::ExportFailed,
+                format!("could not export the turn report: {}", err),
+                None,
+            ),
+        }
+    }
+
+    /// Restores the game from a save file
+    fn load_game(&mut self, app: &App, mut save: SaveFile) {
+        save.drop_missing_systems(self.galaxy.systems.len());
+        self.turn = save.turn;
+        self.schedule.rebase(save.turn);
+        self.bookmarks = save.bookmarks;
+        self.credits = save.credits;
+        // Saves from before colonies were saved keep the new game's colonies
+        if !save.colonies.is_empty() {
+            self.colonies = save.colonies;
+        }
+        let ships: Vec<Entity> = self
+            .world
+            .query::<&Ship>()
+            .iter()
+            .map(|(entity, _ship)| entity)
+            .collect();
+        for entity in ships {
+            self.world.despawn(entity).unwrap();
+        }
+        for ship in save.ships {
+            self.world.spawn((ship,));
+        }
+        self.station_defense();
+        // The system is entered even if it's the one already shown, so that the saved colonies' stations are spawned
+        if save.current_system < self.galaxy.systems.len() {
+            self.enter_system(app, save.current_system);
+            self.history = FocusHistory::new(Focus {
+                system: save.current_system,
+                selection: self.selection,
+            });
         }
     }
 
     /// Changes various game state based on user mouse and keyboard input
     fn control(&mut self, app: &App) {
-        let curr_enter_state = app.keys[Scancode::Return as usize];
-        if curr_enter_state && !self.prev_enter_state {
-            self.selection += 1;
-            self.selected_body_radius = 100.0;
-            self.prev_selected_pos = self.selected_pos;
-            self.transition = app.seconds;
-            if self.selection >= self.bodies.len() {
-                self.selection = 0;
-            }
+        if self.input.pressed(Action::SelectNext) {
+            self.select(app, (self.selection + 1) % self.bodies.len());
         }
-        self.prev_enter_state = curr_enter_state;
 
-        let control_speed = 0.005;
+        let config = self.camera_mode.mode.config();
+        let control_speed = self.settings.camera_sensitivity();
         let zoom_control_speed = 0.15 * (self.distance - self.selected_body_radius);
-        if app.mouse_left_down {
+        let zoom = self.input.zoom(app);
+        if self.input.is_down(Action::Rotate) {
             self.phi -= control_speed * (app.mouse_vel.x as f32);
             self.theta = (self.theta - control_speed * (app.mouse_vel.y as f32))
                 .max(control_speed - PI / 2.0)
                 .min(PI / 2.0 - control_speed);
         }
+        if let Some(theta) = config.locked_theta {
+            self.theta = theta;
+        }
 
-        self.distance = (self.distance - zoom_control_speed * (app.mouse_wheel as f32))
-            .max(self.selected_body_radius * 2.0)
-            .min(self.selected_body_radius * 40000.0 + 234.0);
+        if let Some(limits) = config.zoom {
+            let max_distance = limits.max_distance(self.selected_body_radius);
+            if config.zoom_out_to_galaxy && zoom < 0.0 && self.distance >= max_distance {
+                // Zooming out past the whole system goes to the galaxy map
+                self.view = View::Galaxy;
+            }
+            self.distance = limits.clamp(
+                self.distance - zoom_control_speed * zoom,
+                self.selected_body_radius,
+            );
+        }
+
+        if config.fly_speed > 0.0 {
+            let forward = self.view_direction();
+            let right =
+                nalgebra_glm::cross(&forward, &nalgebra_glm::vec3(0.0, 0.0, 1.0)).normalize();
+            for (action, direction) in [
+                (Action::FlyForward, forward),
+                (Action::FlyBack, -forward),
+                (Action::FlyRight, right),
+                (Action::FlyLeft, -right),
+            ] {
+                if self.input.is_down(action) {
+                    self.fly_pos += direction * config.fly_speed;
+                }
+            }
+        }
+    }
+
+    /// Starts swooshing the camera from where it is over to where the new camera mode puts it
+    fn change_camera_mode(&mut self, app: &App) {
+        self.prev_camera_pos = self.camera_pos;
+        self.prev_camera_lookat = self.camera_lookat;
+        self.mode_swoosh = Tween::new(0.0, 1.0, app.seconds, SWOOSH_DURATION, Easing::CubicOut);
+        // Free-fly starts from wherever the camera was, looking the same way
+        self.fly_pos = self.camera_pos;
+
+        let config = self.camera_mode.mode.config();
+        if let Some(zoom) = config.zoom {
+            self.distance = zoom.clamp(self.distance, self.selected_body_radius);
+        }
+        if let Some(theta) = config.locked_theta {
+            self.theta = theta;
+        }
+    }
+
+    /// The rotation of the camera around what it's looking at, from the view angles
+    fn view_rotation(&self, phi: f32) -> nalgebra_glm::Mat4 {
+        nalgebra_glm::rotate_y(
+            &nalgebra_glm::rotate_z(&nalgebra_glm::one(), phi),
+            self.theta,
+        )
+    }
+
+    /// Which way the camera looks, from the view angles
+    fn view_direction(&self) -> nalgebra_glm::Vec3 {
+        -(self.view_rotation(self.phi) * nalgebra_glm::vec4(1.0, 0., 0., 0.)).xyz()
+    }
+
+    /// What the mouse is over, and its tooltip text as rich text markup. Buttons are in front of the bodies or systems
+    /// behind them
+    fn hovered_tooltip(&self, app: &App) -> Option<(TooltipTarget, String)> {
+        for (_entity, button) in self.world.query::<&Button>().iter() {
+            if button.is_hovered(app) {
+                let mut markup = button.tooltip();
+                if button.id() == "next-turn" {
+                    markup += format!(
+                        "\n[color=grey]shortcut: {}[/color]",
+                        self.input.binding(Action::EndTurn).name()
+                    )
+                    .as_str();
+                }
+                return Some((TooltipTarget::Widget(button.id()), markup));
+            }
+        }
+
+        match self.view {
+            View::System => {
+                let entity = self
+                    .projection
+                    .pick(app.mouse_pos, &body_spheres(&self.world))?;
+                let colony = self.colonies.iter().find(|colony| {
+                    colony.system == self.current_system
+                        && self.bodies.get(colony.body) == Some(&entity)
+                });
+                let mut markup = body_tooltip(&self.world, entity, colony);
+                if colony.is_some() {
+                    markup += format!(
+                        "\n[color=grey]colony - select and press {} to build[/color]",
+                        self.input.binding(Action::Construction).name()
+                    )
+                    .as_str();
+                }
+                Some((TooltipTarget::Body(entity), markup))
+            }
+            View::Galaxy => {
+                let system = self.galaxy_map.hovered()?;
+                let knowledge = &self.knowledge[system];
+                let mut lines = vec![format!("[b]{}[/b]", self.galaxy.systems[system].name)];
+                if system == self.current_system {
+                    lines.push("you are here".to_string());
+                }
+                lines.push(
+                    if knowledge.explored {
+                        "explored"
+                    } else {
+                        "[color=grey]unexplored[/color]"
+                    }
+                    .to_string(),
+                );
+                if knowledge.hostile {
+                    lines.push("[color=red]hostile[/color]".to_string());
+                }
+                if knowledge.defense > 0 {
+                    lines.push(format!(
+                        "[color=green]defense {}[/color]",
+                        knowledge.defense
+                    ));
+                }
+                if system != self.current_system {
+                    if let Some(route) = &self.planned_route {
+                        lines.push(format!(
+                            "{} jumps, eta: {} turns",
+                            route.systems.len() - 1,
+                            route.eta()
+                        ));
+                    }
+                    lines.push("[color=grey]click to enter[/color]".to_string());
+                }
+                Some((TooltipTarget::System(system), lines.join("\n")))
+            }
+        }
+    }
+
+    /// Whether the current turn has finished playing out, so that the next one can start
+    fn turn_finished(&self, app: &App) -> bool {
+        self.turn_progress.is_finished(app.seconds)
+    }
+
+    /// Resolves the current turn and starts the next one. Called by both the next-turn button and real-time mode
+    fn next_turn(&mut self, app: &App) {
+        self.turn += 1;
+        // In real-time mode turns run back to back, so easing each one would make orbits stutter
+        let easing = if self.time_control.is_running() {
+            Easing::Linear
+        } else {
+            Easing::CubicOut
+        };
+        self.turn_progress = Tween::new(
+            0.0,
+            1.0,
+            app.seconds,
+            self.time_control.turn_duration(),
+            easing,
+        );
+        self.notifications.post(
+            self.turn,
+            NotificationKind::TurnStarted,
+            format!(
+                "turn {} began, it is now {}",
+                self.turn,
+                self.calendar.format_turn(self.turn)
+            ),
+            None,
+        );
+        for message in self.schedule.take_due(self.turn) {
+            self.notifications.post(
+                self.turn,
+                NotificationKind::Milestone,
+                message.to_string(),
+                None,
+            );
+        }
+        self.resolve_construction(app);
+        self.station_sensors();
+        self.station_defense();
+
+        let snapshot = self.snapshot();
+        self.turn_report.set_report(TurnReport::new(
+            self.turn,
+            self.calendar.format_turn(self.turn),
+            &snapshot,
+            &self.last_snapshot,
+            self.notifications.turn_log(self.turn),
+        ));
+        self.last_snapshot = snapshot;
+        if self.export_reports {
+            self.export_turn_report();
+        }
+        // In real-time mode turns run back to back, so the report is only opened on request
+        if !self.time_control.is_running() {
+            self.turn_report.open = true;
+        }
+    }
+
+    /// Despawns the bodies of the current system, and spawns the bodies of another system in their place
+    fn enter_system(&mut self, app: &App, system: usize) {
+        for entity in self.bodies.drain(..) {
+            self.world.despawn(entity).unwrap();
+        }
+        self.orbit_fades.clear();
+        self.bvh = BVH::<Entity>::new();
+        self.bodies = self.galaxy.spawn_system(
+            system,
+            &mut self.world,
+            &app.renderer,
+            &mut self.bvh,
+            self.settings.orbit_segments(),
+        );
+        habitability_system(&mut self.world);
+        // Colonies from a stale save can point at bodies this system doesn't have
+        let body_count = self.bodies.len();
+        self.colonies
+            .retain_mut(|colony| colony.system != system || colony.fit_to_bodies(body_count));
+        for colony in self
+            .colonies
+            .iter()
+            .filter(|colony| colony.system == system)
+        {
+            for (i, body) in colony.stations.iter().enumerate() {
+                let index = colony.stations[..i]
+                    .iter()
+                    .filter(|other| *other == body)
+                    .count();
+                let station = spawn_station(
+                    &mut self.world,
+                    &app.renderer,
+                    &mut self.bvh,
+                    self.settings.orbit_segments(),
+                    self.bodies[*body],
+                    index,
+                );
+                self.bodies.push(station);
+            }
+        }
+        self.current_system = system;
+        if !self.knowledge[system].explored {
+            self.knowledge[system].explored = true;
+            self.notifications.post(
+                self.turn,
+                NotificationKind::SystemExplored,
+                format!("explored {}", self.galaxy.systems[system].name),
+                self.bodies.first().copied(),
+            );
+        }
+
+        // Start looking at the star, without swooshing over from where the old system's body was
+        self.selection = 0;
+        self.selected_pos = nalgebra_glm::vec3(0.0, 0.0, 0.0);
+        self.prev_selected_pos = self.selected_pos;
+        self.distance = 2000.0;
+    }
+
+    /// Saves the camera to a bookmark, jumps to a bookmark, or goes back or forward in the focus history
+    fn bookmark_action(&mut self, app: &App, action: BookmarkAction) {
+        match action {
+            BookmarkAction::Save(i) => {
+                self.bookmarks[i] = Some(CameraBookmark {
+                    system: self.current_system,
+                    selection: self.selection,
+                    phi: self.phi,
+                    theta: self.theta,
+                    distance: self.distance,
+                });
+            }
+            BookmarkAction::Recall(i) => {
+                let Some(bookmark) = self.bookmarks[i] else {
+                    return;
+                };
+                let focus = Focus {
+                    system: bookmark.system,
+                    selection: bookmark.selection,
+                };
+                if self.focus_on(app, focus) {
+                    self.history.push(focus);
+                    self.phi = bookmark.phi;
+                    self.theta = bookmark.theta;
+                    self.distance = bookmark.distance;
+                }
+            }
+            BookmarkAction::Back => {
+                if let Some(focus) = self.history.back() {
+                    self.focus_on(app, focus);
+                }
+            }
+            BookmarkAction::Forward => {
+                if let Some(focus) = self.history.forward() {
+                    self.focus_on(app, focus);
+                }
+            }
+        }
+    }
+
+    /// Enters a body's system if the camera isn't already there, and selects it. Returns false if the body doesn't
+    /// exist, which can happen with bookmarks from a save file of a different galaxy
+    fn focus_on(&mut self, app: &App, focus: Focus) -> bool {
+        if focus.system >= self.galaxy.systems.len() {
+            return false;
+        }
+        if focus.system != self.current_system {
+            self.enter_system(app, focus.system);
+        }
+        if focus.selection >= self.bodies.len() {
+            return false;
+        }
+        self.selection = focus.selection;
+        self.selected_body_radius = 100.0;
+        self.prev_selected_pos = self.selected_pos;
+        self.swoosh = Tween::new(0.0, 1.0, app.seconds, SWOOSH_DURATION, Easing::CubicOut);
+        true
+    }
+
+    /// Selects a planetary body by its index in `bodies`, and swooshes the camera over to it
+    fn select(&mut self, app: &App, index: usize) {
+        let focus = Focus {
+            system: self.current_system,
+            selection: index,
+        };
+        self.history.push(focus);
+        self.focus_on(app, focus);
     }
 
     /// Updates planets based on their on-rails orbits around their parent bodies
@@ -386,41 +1531,36 @@ impl Gameplay {
                 continue;
             }
 
-            const REAL_SECS_PER_GAME_YEAR: f32 = 60.0; // How many turns it takes for earth to go around the sun once
-            const T_SEED: f32 = 98400.0; // An offset from t, so that the planets are not all in a line.
-            let t = self.turn as f32
-                + cubic_ease_out((app.seconds - self.turn_transition_time).min(1.0));
-
-            if planet.tier != 0 {
-                let parent_pos = parent_pos_map.get(&planet.parent_planet_id).unwrap();
-                let new_pos = nalgebra_glm::vec3(
-                    (2.0 * PI * (t + T_SEED)
-                        / (REAL_SECS_PER_GAME_YEAR * planet.orbital_time_years))
-                        .cos()
-                        * planet.orbital_radius
-                        + parent_pos.x,
-                    (2.0 * PI * (t + T_SEED)
-                        / (REAL_SECS_PER_GAME_YEAR * planet.orbital_time_years))
-                        .sin()
-                        * planet.orbital_radius
-                        + parent_pos.y,
+            const T_SEED: f32 = 98400.0; // An offset from the turn, so that the planets are not all in a line.
+            let t = self
+                .calendar
+                .years(self.turn as f32 + self.turn_progress.value(app.seconds) + T_SEED);
+
+            // Bodies without a parent orbit the barycenter of the system, at the origin
+            let parent_pos = parent_pos_map
+                .get(&planet.parent_planet_id)
+                .copied()
+                .unwrap_or(nalgebra_glm::vec3(0.0, 0.0, 0.0));
+            let angle = 2.0 * PI * t / planet.orbital_time_years + planet.orbital_phase;
+            let new_pos = if planet.orbital_time_years != 0.0 {
+                nalgebra_glm::vec3(
+                    angle.cos() * planet.orbital_radius + parent_pos.x,
+                    angle.sin() * planet.orbital_radius + parent_pos.y,
                     0.0,
-                );
-                let vel = new_pos - model.get_position();
-                model.set_position(new_pos);
-                self.bvh.move_obj(
-                    planet.bvh_node_id,
-                    &app.renderer.get_model_aabb(&model),
-                    &vel,
-                );
+                )
             } else {
-                model.set_position(nalgebra_glm::vec3(0.0, 0.0, 0.0));
-            }
+                parent_pos
+            };
+            let vel = new_pos - model.get_position();
+            model.set_position(new_pos);
+            self.bvh.move_obj(
+                planet.bvh_node_id,
+                &app.renderer.get_model_aabb(&model),
+                &vel,
+            );
 
             if planet.day_time_years != 0.0 {
-                planet.rotation = 2.0 * PI * (t + T_SEED)
-                    / (REAL_SECS_PER_GAME_YEAR * planet.day_time_years)
-                    + 3.14;
+                planet.rotation = 2.0 * PI * t / planet.day_time_years + 3.14;
             }
 
             if entity == self.bodies[self.selection] {
@@ -430,40 +1570,74 @@ impl Gameplay {
         }
     }
 
-    fn orbit_system(&mut self, _app: &App) {
+    fn orbit_system(&mut self, app: &App) {
         let mut parent_pos_map = HashMap::new();
         for (entity, (model, _planet)) in self.world.query::<(&ModelComponent, &Planet)>().iter() {
             parent_pos_map.insert(entity, model.get_position());
         }
 
+        let selected = self.bodies[self.selection];
         for (entity, (planet, orbit)) in self.world.query_mut::<(&Planet, &mut LinePathComponent)>()
         {
-            let parent_pos = parent_pos_map.get(&planet.parent_planet_id).unwrap();
-            orbit.color.w = if entity == self.bodies[self.selection] {
-                0.8
+            let parent_pos = parent_pos_map
+                .get(&planet.parent_planet_id)
+                .copied()
+                .unwrap_or(nalgebra_glm::vec3(0.0, 0.0, 0.0));
+            let alpha = if entity == selected {
+                SELECTED_ORBIT_ALPHA
             } else {
-                0.2
+                ORBIT_ALPHA
             };
-            orbit.position = *parent_pos;
+            let fade = self
+                .orbit_fades
+                .entry(entity)
+                .or_insert_with(|| Tween::fixed(alpha));
+            if fade.end_value() != alpha {
+                fade.retarget(app.seconds, alpha, ORBIT_FADE_DURATION, Easing::SineInOut);
+            }
+            orbit.color.w = fade.value(app.seconds);
+            orbit.position = parent_pos;
         }
     }
 
-    /// Updates the camera position and lookat based on mouse panning and body selection
+    /// Updates the camera position and lookat based on the camera mode, mouse panning and body selection
     fn camera_update(&mut self, app: &App) {
-        let rot_matrix = nalgebra_glm::rotate_y(
-            &nalgebra_glm::rotate_z(&nalgebra_glm::one(), self.phi),
-            self.theta,
-        );
-        let transition = cubic_ease_out((app.seconds - self.transition).min(1.0));
+        let transition = self.swoosh.value(app.seconds);
         let offset = (1.0 - transition) * self.prev_selected_pos + transition * self.selected_pos;
-        self.camera_3d.set_position(
-            (rot_matrix * nalgebra_glm::vec4(self.distance, 0., 0., 0.)).xyz() + offset,
+        let swivel = nalgebra_glm::vec4(self.distance, 0., 0., 0.);
+        let (camera_pos, camera_lookat) = match self.camera_mode.mode {
+            CameraMode::Orbit => (
+                (self.view_rotation(self.phi) * swivel).xyz() + offset,
+                self.selected_pos,
+            ),
+            CameraMode::Track => {
+                // Turn with the body as it goes around the barycenter
+                let heading = self.selected_pos.y.atan2(self.selected_pos.x);
+                (
+                    (self.view_rotation(self.phi + heading) * swivel).xyz() + offset,
+                    self.selected_pos,
+                )
+            }
+            CameraMode::TopDown => (
+                (self.view_rotation(self.phi) * swivel).xyz(),
+                nalgebra_glm::vec3(0.0, 0.0, 0.0),
+            ),
+            CameraMode::FreeFly => (self.fly_pos, self.fly_pos + self.view_direction()),
+        };
+
+        let mode_transition = self.mode_swoosh.value(app.seconds);
+        self.camera_pos = nalgebra_glm::lerp(&self.prev_camera_pos, &camera_pos, mode_transition);
+        self.camera_lookat =
+            nalgebra_glm::lerp(&self.prev_camera_lookat, &camera_lookat, mode_transition);
+        self.camera_3d.set_position(self.camera_pos);
+        self.camera_3d.set_lookat(self.camera_lookat);
+
+        self.projection = ScreenProjection::new(
+            self.camera_pos,
+            self.camera_lookat,
+            CAMERA_FOV,
+            CAMERA_FAR,
+            nalgebra_glm::vec2(app.window_size.x as f32, app.window_size.y as f32),
         );
-        self.camera_3d.set_lookat(self.selected_pos);
     }
 }
-
-/// Cubic easing out function - for animation
-fn cubic_ease_out(t: f32) -> f32 {
-    1.0 - (1.0 - t).powf(3.0)
-}
@@ -1,6 +1,7 @@
 #version 330 core
 
 uniform float u_opacity;
+uniform vec3 u_color;
 uniform sampler2D texture0;
 
 uniform vec2 u_sprite_size;   // size of a single sprite in pixels
@@ -13,4 +14,6 @@ out vec4 Color;
 void main()
 {
     Color = texture(texture0, uv.xy * u_sprite_size + u_sprite_offset);
+    Color.rgb *= u_color;
+    Color.a *= u_opacity;
 }
\ No newline at end of file
@@ -2,49 +2,68 @@
 
 in vec3 texCoord;
 in vec3 color;
-in vec3 Normal_cameraspace;
-in vec3 LightDirection_cameraspace;
-in vec4 light_space_pos; // For shadow mapping
+in vec3 Position_worldspace;
+in vec3 Normal_worldspace;
 
 out vec4 Color;
 
 uniform sampler2D texture0;
-uniform sampler2D shadow_map;
 
-vec2 poissonDisk[9] = vec2[](
-  vec2( -1.0,  1.0 ),
-  vec2(  0.0,  1.0 ),
-  vec2(  1.0,  1.0 ),
-  vec2( -1.0,  0.0 ),
-  vec2(  0.0,  0.0 ),
-  vec2(  1.0,  0.0 ),
-  vec2( -1.0, -1.0 ),
-  vec2(  0.0, -1.0 ),
-  vec2(  1.0, -1.0 )
-);
+// Every star in the system lights the scene. Must match `MAX_STARS` in `star.rs`
+#define MAX_STARS 4
+uniform int u_star_count;
+uniform vec3 u_star_positions[MAX_STARS];
+uniform vec3 u_star_colors[MAX_STARS];
+uniform float u_star_radii[MAX_STARS];
 
-// x x x
-// x   x
-// x x x
+// Every other body can eclipse the stars. Must match `MAX_OCCLUDERS` in `star.rs`
+#define MAX_OCCLUDERS 32
+uniform int u_occluder_count;
+uniform vec3 u_occluder_positions[MAX_OCCLUDERS];
+uniform float u_occluder_radii[MAX_OCCLUDERS];
 
-float calc_shadow_factor()
+// Returns how much of a star, as seen from this fragment, is not blocked by any other body.
+// Bodies are treated as spheres, with a soft penumbra as wide as the star's apparent size.
+float calc_star_visibility(vec3 to_star, float star_radius)
 {
-    vec3 proj_coords = light_space_pos.xyz / light_space_pos.w;
-    vec2 uv_coords;
-    uv_coords.x = 0.5 * proj_coords.x + 0.5;
-    uv_coords.y = 0.5 * proj_coords.y + 0.5;
-    float z = 0.5 * proj_coords.z + 0.5;
-    float bias = 0.000;
+    float star_dist = length(to_star);
+    vec3 dir = to_star / star_dist;
     float visibility = 1.0;
-    for (int i = 0; i < 9; i++){
-        float depth = texture(shadow_map, uv_coords + poissonDisk[i] / 7000.0).x;
-        if (depth + bias < z) {
-            visibility -= 0.111;
+    for (int i = 0; i < u_occluder_count; i++) {
+        vec3 to_occluder = u_occluder_positions[i] - Position_worldspace;
+        float radius = u_occluder_radii[i];
+        // Bodies don't shadow themselves, their own surface already faces away from the star
+        if (length(to_occluder) <= radius * 1.01) {
+            continue;
         }
+        // How far along the ray to the star the occluder is, it has to be between us and the star
+        float t = dot(to_occluder, dir);
+        if (t <= 0.0 || t >= star_dist) {
+            continue;
+        }
+        float miss_dist = length(to_occluder - dir * t);
+        float penumbra = star_radius * t / star_dist;
+        visibility *= smoothstep(radius - penumbra, radius + penumbra, miss_dist);
     }
     return visibility;
 }
 
+// Sums up the light from every star that reaches this fragment, taking eclipses into account
+vec3 calc_star_light(vec3 n)
+{
+    vec3 light = vec3(0.0);
+    for (int i = 0; i < u_star_count; i++) {
+        vec3 to_star = u_star_positions[i] - Position_worldspace;
+        // Fragments on the surface of a star are lit by the star itself
+        if (length(to_star) <= u_star_radii[i] * 1.01) {
+            return vec3(1.0);
+        }
+        float cos_theta = clamp(dot(n, normalize(to_star)), 0, 1);
+        light += cos_theta * calc_star_visibility(to_star, u_star_radii[i]) * u_star_colors[i];
+    }
+    return light;
+}
+
 vec3 tint(vec3 material, vec3 tint, float strength) {
     float luminance = dot(material, vec3(0.299, 0.587, 0.114));
     return mix(
@@ -60,26 +79,18 @@ void main()
     float texture_alpha = texture_color.w;
     vec3 material_color = texture_color.xyz;
 
-    // Normal of the computed fragment, in camera space
-    vec3 n = normalize( Normal_cameraspace );
-    // Direction of the light, in camera space
-    vec3 l = normalize( LightDirection_cameraspace );
-    // Direction to the eye, in camera space
-    float cosTheta = clamp(dot(n, vec3(l.x, l.y, abs(l.z))), 0, 1);
-
-    vec3 LightColor = vec3(
-      255.0 / 255.0, 
-      100.0 / 255.0, 
-      0.0 / 255.0
-    );
-
-    float shadow_factor = calc_shadow_factor();
+    // Normal of the computed fragment, in world space
+    vec3 n = normalize( Normal_worldspace );
+    vec3 star_light = calc_star_light(n);
 
     vec3 shadow = 0.1 * material_color * vec3(0.1, 0.1, 0.2);
-    vec3 light_tinted = material_color;
+    // Split the light into how bright it is, and what color it is
+    float brightest = max(star_light.r, max(star_light.g, star_light.b));
+    float intensity = clamp(brightest, 0, 1);
+    vec3 light_tinted = material_color * star_light / max(brightest, 0.0001);
     
     float levels = 16.0;
-    float diff = cosTheta * shadow_factor;
+    float diff = intensity;
     diff = floor(diff * levels) / levels;
 
     vec3 color = mix(shadow, light_tinted, diff);
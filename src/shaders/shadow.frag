@@ -1,10 +0,0 @@
-#version 330
-// Empty, we're only rendering into the depth buffer, no color!
-
-// Ouput data
-layout(location = 0) out float fragmentdepth;
-
-void main(){
-    // Not really needed, OpenGL does it anyway
-    fragmentdepth = gl_FragCoord.z;
-}
\ No newline at end of file